@@ -0,0 +1,119 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::{board::Board, checkers::Checkers, minmax::MinMax, player::Player};
+
+/// Search depth used by the `bench` command.
+pub const DEFAULT_BENCH_DEPTH: usize = 16;
+
+/// Positions searched by [`run`], as written by [`Board::encode`], with the player to move.
+/// They cover the opening, middlegame and king endings so changes to any part of the engine
+/// show up in the report.
+const BENCH_POSITIONS: [(&str, Player); 10] = [
+    ("rrrrrrrrrrrr........wwwwwwwwwwww", Player::Red),
+    ("rrrrrrr.rrrr...r...wwwwwwww.wwww", Player::Red),
+    ("rrrr.rr...rr.r.....ww.rw.ww.wwww", Player::White),
+    ("rrrrrr...r.r...rw.....w..wwwwwww", Player::Red),
+    (".rrr.r.....rr.w.....w.......wwww", Player::Red),
+    ("rr.......rr..rr....w.w......wwww", Player::White),
+    ("..r......rr.r..rw....w.......www", Player::White),
+    (".........rr.r.r.w.r.ww.w...w....", Player::Red),
+    ("W....................r.....r..R.", Player::Red),
+    (".................W......RR.....R", Player::White),
+];
+
+/// Totals from searching every benchmark position.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BenchReport {
+    pub depth: usize,
+    pub positions: usize,
+    pub nodes: u64,
+    pub elapsed: Duration,
+    /// Hash of the best move, score and node count of every search. It only changes when the
+    /// search itself behaves differently, not when it just runs faster or slower.
+    pub signature: u64,
+}
+
+impl BenchReport {
+    pub fn nodes_per_second(&self) -> f64 {
+        self.nodes as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+impl Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Positions:  {}", self.positions)?;
+        writeln!(f, "Depth:      {}", self.depth)?;
+        writeln!(f, "Nodes:      {}", self.nodes)?;
+        writeln!(f, "Time:       {:.3}s", self.elapsed.as_secs_f64())?;
+        writeln!(f, "Nodes/s:    {:.0}", self.nodes_per_second())?;
+        write!(f, "Signature:  {:016x}", self.signature)
+    }
+}
+
+/// Searches every benchmark position to `depth` with a default [`MinMax`] bot.
+pub fn run(depth: usize) -> BenchReport {
+    let bot = MinMax::default();
+    let games: Vec<Checkers> = BENCH_POSITIONS
+        .iter()
+        .map(|(board, player)| {
+            Board::decode(board)
+                .and_then(|board| Checkers::setup(board, *player, None))
+                .expect("benchmark positions are valid")
+        })
+        .collect();
+
+    let mut nodes = 0;
+    let mut signature = Fnv::default();
+    let start = Instant::now();
+    for game in games.iter() {
+        let result = bot.search(game, Some(depth));
+        nodes += result.nodes;
+        signature.write(result.best_move.map_or(u64::MAX, |m| m as u64));
+        signature.write(result.score as u64);
+        signature.write(result.nodes);
+    }
+    BenchReport {
+        depth,
+        positions: games.len(),
+        nodes,
+        elapsed: start.elapsed(),
+        signature: signature.0,
+    }
+}
+
+/// FNV-1a, used rather than the standard library hasher so signatures stay the same across
+/// Rust versions.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, value: u64) {
+        for byte in value.to_le_bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_is_deterministic() {
+        let first = run(4);
+        let second = run(4);
+        assert_eq!(first.positions, BENCH_POSITIONS.len());
+        assert!(first.nodes > first.positions as u64);
+        assert_eq!(first.nodes, second.nodes);
+        assert_eq!(first.signature, second.signature);
+    }
+}
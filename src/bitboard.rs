@@ -0,0 +1,223 @@
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    iter::from_fn,
+    ops::{BitAnd, BitOr, Not, Shl, Shr, Sub},
+};
+
+use crate::{piece::Piece, player::Player};
+
+/// An unsigned integer used as a set of board positions, position `n` in bit `n`. A board uses
+/// the narrowest one its positions fit in: `u32` for 8x8 boards, `u64` for 10x10 boards and
+/// boards played on every square of 8x8, `u128` for 12x12 boards.
+pub(crate) trait Bits:
+    Copy
+    + Eq
+    + Hash
+    + Default
+    + Debug
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + Not<Output = Self>
+    + Sub<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    const BITS: u32;
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn trailing_zeros(self) -> u32;
+
+    #[inline]
+    fn bit(position: usize) -> Self {
+        Self::ONE << position as u32
+    }
+}
+
+macro_rules! impl_bits {
+    ($($t:ty),*) => {$(
+        impl Bits for $t {
+            const BITS: u32 = <$t>::BITS;
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            #[inline]
+            fn trailing_zeros(self) -> u32 {
+                <$t>::trailing_zeros(self)
+            }
+        }
+    )*};
+}
+
+impl_bits!(u32, u64, u128);
+
+/// The positions in `bits`, lowest first.
+pub(crate) fn positions<B: Bits>(mut bits: B) -> impl Iterator<Item = usize> {
+    from_fn(move || {
+        (bits != B::ZERO).then(|| {
+            let position = bits.trailing_zeros() as usize;
+            bits = bits & (bits - B::ONE);
+            position
+        })
+    })
+}
+
+/// A step in one direction for a whole set of positions. The positions in each mask move by the
+/// offset paired with it, positions in neither would leave the board. Rows of dark squares
+/// alternate between starting on the first and the second column, so diagonal steps move
+/// positions on even and odd rows by different offsets.
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct Shift<B>([(B, i32); 2]);
+
+impl<B: Bits> Shift<B> {
+    /// The steps in each direction, from the neighbor in that direction of every position.
+    pub(crate) fn for_neighbors(neighbors: &[[Option<usize>; 4]]) -> [Self; 4] {
+        let mut shifts = [Self::default(); 4];
+        for (position, neighbors) in neighbors.iter().enumerate() {
+            for (shift, neighbor) in shifts.iter_mut().zip(neighbors) {
+                let Some(neighbor) = neighbor else {
+                    continue;
+                };
+                let offset = *neighbor as i32 - position as i32;
+                if let Some(part) = shift
+                    .0
+                    .iter_mut()
+                    .find(|(mask, o)| *mask == B::ZERO || *o == offset)
+                {
+                    *part = (part.0 | B::bit(position), offset);
+                }
+            }
+        }
+        shifts
+    }
+
+    #[inline]
+    fn apply(&self, bits: B) -> B {
+        self.0.iter().fold(B::ZERO, |moved, &(mask, offset)| {
+            moved
+                | if offset >= 0 {
+                    (bits & mask) << offset as u32
+                } else {
+                    (bits & mask) >> offset.unsigned_abs()
+                }
+        })
+    }
+}
+
+/// The pieces on a board, as the positions of each player's pieces and of the kings.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub(crate) struct Pieces<B> {
+    red: B,
+    white: B,
+    kings: B,
+}
+
+impl<B: Bits> Pieces<B> {
+    #[inline]
+    pub(crate) fn get(&self, position: usize) -> Option<Piece> {
+        let bit = B::bit(position);
+        let mut piece = if self.red & bit != B::ZERO {
+            Piece::red()
+        } else if self.white & bit != B::ZERO {
+            Piece::white()
+        } else {
+            return None;
+        };
+        if self.kings & bit != B::ZERO {
+            piece.to_king();
+        }
+        Some(piece)
+    }
+
+    #[inline]
+    pub(crate) fn set(&mut self, position: usize, piece: Option<Piece>) {
+        let bit = B::bit(position);
+        self.red = self.red & !bit;
+        self.white = self.white & !bit;
+        self.kings = self.kings & !bit;
+        if let Some(piece) = piece {
+            match piece.player() {
+                Player::Red => self.red = self.red | bit,
+                Player::White => self.white = self.white | bit,
+            }
+            if piece.is_king() {
+                self.kings = self.kings | bit;
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn of(&self, player: Player) -> B {
+        match player {
+            Player::Red => self.red,
+            Player::White => self.white,
+        }
+    }
+}
+
+/// The pieces of a board with the steps for its size, to move many pieces at once.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Bitboard<'a, B> {
+    pieces: &'a Pieces<B>,
+    shifts: &'a [Shift<B>; 4],
+    directions: &'static [(usize, usize)],
+    empty: B,
+}
+
+impl<'a, B: Bits> Bitboard<'a, B> {
+    /// `shifts` are the steps in each of `directions`, in the same order.
+    pub(crate) fn new(
+        pieces: &'a Pieces<B>,
+        shifts: &'a [Shift<B>; 4],
+        directions: &'static [(usize, usize)],
+        position_count: usize,
+    ) -> Self {
+        let all = if position_count as u32 == B::BITS {
+            !B::ZERO
+        } else {
+            B::bit(position_count) - B::ONE
+        };
+        Self {
+            pieces,
+            shifts,
+            directions,
+            empty: all & !(pieces.red | pieces.white),
+        }
+    }
+
+    /// The positions of `player`'s pieces.
+    #[inline]
+    pub(crate) fn pieces(&self, player: Player) -> B {
+        self.pieces.of(player)
+    }
+
+    /// The positions of the kings of both players.
+    #[inline]
+    pub(crate) fn kings(&self) -> B {
+        self.pieces.kings
+    }
+
+    #[inline]
+    pub(crate) fn empty(&self) -> B {
+        self.empty
+    }
+
+    /// Moves every position in `bits` one step in `direction`, dropping those that would leave
+    /// the board.
+    #[inline]
+    pub(crate) fn shift(&self, bits: B, direction: (usize, usize)) -> B {
+        self.directions
+            .iter()
+            .position(|d| *d == direction)
+            .map_or(B::ZERO, |i| self.shifts[i].apply(bits))
+    }
+}
+
+/// A [`Bitboard`] of whichever width the board uses.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum AnyBitboard<'a> {
+    U32(Bitboard<'a, u32>),
+    U64(Bitboard<'a, u64>),
+    U128(Bitboard<'a, u128>),
+}
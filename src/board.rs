@@ -1,8 +1,22 @@
-use std::{fmt::Display, iter::repeat};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    iter::repeat,
+    sync::Arc,
+};
 
 use tabled::tables::IterTable;
 
-use crate::{piece::Piece, player::Player};
+use crate::{
+    bitboard::{positions, AnyBitboard, Bitboard, Bits, Pieces, Shift},
+    error::CheckersError,
+    nnue::{Accumulator, Network},
+    piece::Piece,
+    player::Player,
+    ruleset::{Movement, Ruleset},
+};
 
 /// A struct that contains a board with numbered posiitions matching those shown here.
 /// Use the [`to_coord`] and [`to_position`] methods to translate positions from left example
@@ -17,48 +31,263 @@ use crate::{piece::Piece, player::Player};
 /// __ 24 __ 25 __ 26 __ 27 || ___ 6,1 ___ 6,3 ___ 6,5 ___ 6,7
 /// 28 __ 29 __ 30 __ 31 __ || 7,0 ___ 7,2 ___ 7,4 ___ 7,6 ___
 ///
+/// Boards for [`Movement::Orthogonal`] play on every square, numbered row by row.
+///
+/// Pieces are kept as bitboards, one bit per position in the narrowest integer that fits the
+/// board, so a step in one direction for every piece at once is a couple of shifts. Boards with
+/// more than 128 positions keep a piece for every position instead. Coordinates, neighbors and
+/// jump landing squares are looked up in tables worked out once for each board size.
+///
 /// # Example
 ///
 /// ```
 /// use kar_checkers_minmax::board::Board;
-/// let board = Board::new(8);
+/// let board = Board::new(8).unwrap();
 /// assert_eq!(board.to_coord(16), (4, 1));
 /// assert_eq!(board.to_position((4, 5)), 18);
 /// ```
-#[derive(Clone, Eq, Hash, Debug)]
+#[derive(Clone)]
 pub struct Board {
-    board: Vec<Option<Piece>>,
+    squares: Squares,
+    /// Number of columns, the number of rows for square boards.
     size: usize,
+    rows: usize,
     half_size: usize,
     position_count: usize,
+    movement: Movement,
+    geometry: Arc<Geometry>,
+    /// First layer of an attached [`Network`], kept up to date as pieces change.
+    accumulator: Option<Accumulator>,
+}
+
+/// The pieces of a board, as bitboards of the narrowest width its positions fit in or one
+/// entry for every position when there are too many.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum Squares {
+    U32(Pieces<u32>),
+    U64(Pieces<u64>),
+    U128(Pieces<u128>),
+    Each(Vec<Option<Piece>>),
+}
+
+impl Squares {
+    fn empty(position_count: usize) -> Self {
+        match position_count as u32 {
+            count if count <= u32::BITS => Squares::U32(Pieces::default()),
+            count if count <= u64::BITS => Squares::U64(Pieces::default()),
+            count if count <= u128::BITS => Squares::U128(Pieces::default()),
+            _ => Squares::Each(vec![None; position_count]),
+        }
+    }
+}
+
+/// Tables for the squares of boards of one size and movement, shared by all of them. The
+/// entries for each direction follow the order of [`Movement::directions`].
+struct Geometry {
+    coords: Vec<(usize, usize)>,
+    /// The position one step away in each direction, for every position.
+    neighbors: Vec<[Option<usize>; 4]>,
+    /// The position two steps away in each direction, where a jump from there lands.
+    jumps: Vec<[Option<usize>; 4]>,
+    /// Steps in each direction for bitboards of each width the board fits in.
+    shifts_u32: [Shift<u32>; 4],
+    shifts_u64: [Shift<u64>; 4],
+    shifts_u128: [Shift<u128>; 4],
+}
+
+impl Geometry {
+    /// The tables for boards of `columns` columns and `rows` rows, worked out the first time
+    /// a thread asks for them. Each thread keeps its own, so boards are made without locking.
+    fn get(columns: usize, rows: usize, movement: Movement) -> Arc<Self> {
+        type Cache = HashMap<(usize, usize, Movement), Arc<Geometry>>;
+        thread_local! {
+            static CACHE: RefCell<Cache> = RefCell::default();
+        }
+        CACHE.with(|cache| {
+            cache
+                .borrow_mut()
+                .entry((columns, rows, movement))
+                .or_insert_with(|| Arc::new(Self::new(columns, rows, movement)))
+                .clone()
+        })
+    }
+
+    fn new(columns: usize, rows: usize, movement: Movement) -> Self {
+        let row_length = Board::row_length(columns, movement);
+        let to_coord = |position: usize| match movement {
+            Movement::Diagonal => {
+                let row = position / row_length;
+                (row, position % row_length * 2 + (row + 1) % 2)
+            }
+            Movement::Orthogonal => (position / columns, position % columns),
+        };
+        let to_position = |(row, col): (usize, usize)| match movement {
+            Movement::Diagonal => row * row_length + col / 2,
+            Movement::Orthogonal => row * row_length + col,
+        };
+        let step = |position: usize, direction: (usize, usize)| {
+            let (row, col) = to_coord(position);
+            let coord = (row.wrapping_add(direction.0), col.wrapping_add(direction.1));
+            (coord.0 < rows && coord.1 < columns).then(|| to_position(coord))
+        };
+
+        let position_count = rows * row_length;
+        let mut geometry = Self {
+            coords: (0..position_count).map(to_coord).collect(),
+            neighbors: vec![[None; 4]; position_count],
+            jumps: vec![[None; 4]; position_count],
+            shifts_u32: Default::default(),
+            shifts_u64: Default::default(),
+            shifts_u128: Default::default(),
+        };
+        for (i, direction) in movement.directions().iter().enumerate() {
+            for position in 0..position_count {
+                let Some(neighbor) = step(position, *direction) else {
+                    continue;
+                };
+                geometry.neighbors[position][i] = Some(neighbor);
+                geometry.jumps[position][i] = step(neighbor, *direction);
+            }
+        }
+        geometry.shifts_u32 = Self::shifts(&geometry.neighbors);
+        geometry.shifts_u64 = Self::shifts(&geometry.neighbors);
+        geometry.shifts_u128 = Self::shifts(&geometry.neighbors);
+        geometry
+    }
+
+    /// The steps for bitboards of width `B`, none if the positions do not fit in it.
+    fn shifts<B: Bits>(neighbors: &[[Option<usize>; 4]]) -> [Shift<B>; 4] {
+        if neighbors.len() > B::BITS as usize {
+            return Default::default();
+        }
+        Shift::for_neighbors(neighbors)
+    }
 }
 
 impl Board {
-    pub fn new(board_size: usize) -> Self {
-        Self::validate_board_size(board_size);
-        let half_size = board_size / 2;
-        let position_count = board_size.pow(2) / 2;
-        let board = (0..half_size * 3)
-            .map(|_| Some(Piece::red()))
-            .chain((0..half_size * (board_size - 6)).map(|_| None))
-            .chain((0..half_size * 3).map(|_| Some(Piece::white())))
+    /// Creates a square board with [`Board::default_starting_rows`] rows of men for each
+    /// player.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kar_checkers_minmax::{board::Board, player::Player};
+    /// assert_eq!(Board::new(6)?.get_player_piece_positions(Player::Red).count(), 6);
+    /// assert_eq!(Board::new(8)?.get_player_piece_positions(Player::Red).count(), 12);
+    /// assert!(Board::new(7).is_err());
+    /// # Ok::<(), kar_checkers_minmax::error::CheckersError>(())
+    /// ```
+    pub fn new(board_size: usize) -> Result<Self, CheckersError> {
+        Self::with_starting_rows(board_size, Self::default_starting_rows(board_size))
+    }
+
+    /// Rows of men each player starts with on a board with `rows` rows, leaving the two
+    /// middle rows empty.
+    pub fn default_starting_rows(rows: usize) -> usize {
+        rows.saturating_sub(2) / 2
+    }
+
+    /// Creates a board with `rows` rows of men for each player, e.g. 4 for international
+    /// draughts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kar_checkers_minmax::{board::Board, player::Player};
+    /// let board = Board::with_starting_rows(10, 4).unwrap();
+    /// assert_eq!(board.get_player_piece_positions(Player::Red).count(), 20);
+    /// assert_eq!(board.get_player_piece_positions(Player::White).count(), 20);
+    /// ```
+    pub fn with_starting_rows(board_size: usize, rows: usize) -> Result<Self, CheckersError> {
+        Self::with_setup(board_size, board_size, Movement::Diagonal, rows, 0)
+    }
+
+    /// Creates a board with `columns` columns and `rows` rows, and `starting_rows` rows of men
+    /// for each player.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kar_checkers_minmax::{board::Board, player::Player};
+    /// let board = Board::rectangular(8, 10, 4).unwrap();
+    /// assert_eq!(board.rows(), 10);
+    /// assert_eq!(board.get_player_piece_positions(Player::White).count(), 16);
+    /// ```
+    pub fn rectangular(
+        columns: usize,
+        rows: usize,
+        starting_rows: usize,
+    ) -> Result<Self, CheckersError> {
+        Self::with_setup(columns, rows, Movement::Diagonal, starting_rows, 0)
+    }
+
+    /// Creates the starting board of a game played by `rules`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kar_checkers_minmax::{board::Board, player::Player, ruleset::Ruleset};
+    /// let board = Board::for_rules(&Ruleset::turkish()).unwrap();
+    /// assert_eq!(board.position_count(), 64);
+    /// assert_eq!(board.get_player_piece_positions(Player::Red).count(), 16);
+    /// assert_eq!(board.get(0), None);
+    /// ```
+    pub fn for_rules(rules: &Ruleset) -> Result<Self, CheckersError> {
+        Self::with_setup(
+            rules.board_size,
+            rules.board_rows.unwrap_or(rules.board_size),
+            rules.movement,
+            rules.starting_rows,
+            rules.empty_back_rows,
+        )
+    }
+
+    fn with_setup(
+        columns: usize,
+        rows: usize,
+        movement: Movement,
+        starting_rows: usize,
+        empty_rows: usize,
+    ) -> Result<Self, CheckersError> {
+        Self::validate_board_size(rows)?;
+        if (starting_rows + empty_rows) * 2 >= rows {
+            return Err(CheckersError::MalformedPosition(format!(
+                "{} starting and {} empty rows for each player do not fit on {} rows",
+                starting_rows, empty_rows, rows
+            )));
+        }
+        let row_length = Self::row_length(columns, movement);
+        let middle_rows = rows - (starting_rows + empty_rows) * 2;
+        let board = (0..row_length * empty_rows)
+            .map(|_| None)
+            .chain((0..row_length * starting_rows).map(|_| Some(Piece::red())))
+            .chain((0..row_length * middle_rows).map(|_| None))
+            .chain((0..row_length * starting_rows).map(|_| Some(Piece::white())))
+            .chain((0..row_length * empty_rows).map(|_| None))
             .collect();
+        Self::with_layout(columns, movement, board)
+    }
 
-        Self {
-            board,
-            size: board_size,
-            half_size,
-            position_count,
+    #[inline]
+    fn row_length(board_size: usize, movement: Movement) -> usize {
+        match movement {
+            Movement::Diagonal => board_size / 2,
+            Movement::Orthogonal => board_size,
         }
     }
 
-    /// For testing. Creates a Board with a supplied layout.
+    /// Creates a Board for `movement` with a supplied layout of rows of `columns` columns, which
+    /// covers every square for [`Movement::Orthogonal`] boards and only the dark squares
+    /// otherwise. This is how a position is built for [`crate::checkers::Checkers::setup`].
+    /// The number of rows is taken from the length of the layout, so a board with another
+    /// number of rows than columns is only accepted by `setup` along with rules giving its
+    /// rows. Only the shape of the board is checked here, `setup` also checks the pieces.
     ///
     /// # Example
     ///
     /// ```
-    /// use kar_checkers_minmax::{board::Board, piece::Piece};
-    /// let board = Board::with_layout(8, vec![
+    /// use kar_checkers_minmax::{board::Board, piece::Piece, ruleset::Movement};
+    /// let board = Board::with_layout(8, Movement::Diagonal, vec![
     ///     Some(Piece::red()), Some(Piece::red()), Some(Piece::red()), Some(Piece::red()),
     ///     Some(Piece::red()), Some(Piece::red()), Some(Piece::red()), Some(Piece::red()),
     ///     Some(Piece::red()), Some(Piece::red()), Some(Piece::red()), Some(Piece::red()),
@@ -67,31 +296,59 @@ impl Board {
     ///     Some(Piece::white()), Some(Piece::white()), Some(Piece::white()), Some(Piece::white()),
     ///     Some(Piece::white()), Some(Piece::white()), Some(Piece::white()), Some(Piece::white()),
     ///     Some(Piece::white()), Some(Piece::white()), Some(Piece::white()), Some(Piece::white()),
-    /// ]);
-    /// assert_eq!(board, Board::new(8));
+    /// ]).unwrap();
+    /// assert_eq!(board, Board::new(8).unwrap());
     /// ```
-    pub fn with_layout(board_size: usize, board: Vec<Option<Piece>>) -> Self {
-        Self::validate_board_size(board_size);
-        Self {
-            board,
-            size: board_size,
-            half_size: board_size / 2,
-            position_count: board_size.pow(2) / 2,
+    pub fn with_layout(
+        columns: usize,
+        movement: Movement,
+        board: Vec<Option<Piece>>,
+    ) -> Result<Self, CheckersError> {
+        Self::validate_board_size(columns)?;
+        let rows = board.len() / Self::row_length(columns, movement);
+        Self::validate_board_size(rows)?;
+        if board.len() != rows * Self::row_length(columns, movement) {
+            return Err(CheckersError::MalformedPosition(format!(
+                "{} positions do not fill whole rows of {} columns",
+                board.len(),
+                columns
+            )));
+        }
+        let mut new_board = Self {
+            squares: Squares::empty(board.len()),
+            size: columns,
+            rows,
+            half_size: columns / 2,
+            position_count: board.len(),
+            movement,
+            geometry: Geometry::get(columns, rows, movement),
+            accumulator: None,
+        };
+        for (position, piece) in board.into_iter().enumerate() {
+            new_board.set(position, piece);
         }
+        Ok(new_board)
     }
 
     #[inline]
-    fn validate_board_size(board_size: usize) {
+    fn validate_board_size(board_size: usize) -> Result<(), CheckersError> {
         if board_size % 2 == 1 || board_size < 6 {
-            panic!("invalid board size");
+            return Err(CheckersError::InvalidBoardSize(board_size));
         }
+        Ok(())
     }
 
+    /// Number of columns, which is also the number of rows unless the board is rectangular.
     #[inline]
     pub fn size(&self) -> usize {
         self.size
     }
 
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
     #[inline]
     pub fn half_size(&self) -> usize {
         self.half_size
@@ -103,94 +360,323 @@ impl Board {
     }
 
     #[inline]
-    pub fn get(&self, position: usize) -> Option<Piece> {
-        self.board[position]
+    pub fn movement(&self) -> Movement {
+        self.movement
     }
 
     #[inline]
-    pub fn get_mut(&mut self, position: usize) -> Option<&mut Piece> {
-        self.board[position].as_mut()
+    fn check_position(&self, position: usize) {
+        assert!(
+            position < self.position_count,
+            "position {} is not on the board",
+            position
+        );
     }
 
+    /// The piece on `position`, if there is one.
+    ///
+    /// # Panics
+    ///
+    /// If `position` is not on the board, that is not below [`Board::position_count`].
+    #[inline]
+    pub fn get(&self, position: usize) -> Option<Piece> {
+        self.check_position(position);
+        match &self.squares {
+            Squares::U32(pieces) => pieces.get(position),
+            Squares::U64(pieces) => pieces.get(position),
+            Squares::U128(pieces) => pieces.get(position),
+            Squares::Each(squares) => squares[position],
+        }
+    }
+
+    /// Puts `new_value` on `position`, replacing whatever was there.
+    ///
+    /// # Panics
+    ///
+    /// If `position` is not on the board, like [`Board::get`]. So do [`Board::take`],
+    /// `Board::r#move` and [`Board::crown`], which go through it.
     #[inline]
     pub fn set(&mut self, position: usize, new_value: Option<Piece>) {
-        self.board[position] = new_value;
+        let old_value = self.get(position);
+        match &mut self.squares {
+            Squares::U32(pieces) => pieces.set(position, new_value),
+            Squares::U64(pieces) => pieces.set(position, new_value),
+            Squares::U128(pieces) => pieces.set(position, new_value),
+            Squares::Each(squares) => squares[position] = new_value,
+        }
+        self.update_accumulator(position, old_value, new_value);
     }
 
     #[inline]
     pub fn r#move(&mut self, start_pos: usize, end_pos: usize) {
-        self.board[end_pos] = std::mem::take(&mut self.board[start_pos]);
+        let piece = self.take(start_pos);
+        self.set(end_pos, piece);
     }
 
     #[inline]
     pub fn take(&mut self, position: usize) -> Option<Piece> {
-        std::mem::take(&mut self.board[position])
+        let piece = self.get(position);
+        self.set(position, None);
+        piece
+    }
+
+    /// Turns the piece at `position` into a king, if there is a piece there.
+    pub fn crown(&mut self, position: usize) {
+        if let Some(mut piece) = self.get(position) {
+            piece.to_king();
+            self.set(position, Some(piece));
+        }
+    }
+
+    /// The pieces as bitboards, `None` for boards too large to keep them that way.
+    #[inline]
+    pub(crate) fn bitboard(&self) -> Option<AnyBitboard<'_>> {
+        let directions = self.movement.directions();
+        let count = self.position_count;
+        Some(match &self.squares {
+            Squares::U32(pieces) => AnyBitboard::U32(Bitboard::new(
+                pieces,
+                &self.geometry.shifts_u32,
+                directions,
+                count,
+            )),
+            Squares::U64(pieces) => AnyBitboard::U64(Bitboard::new(
+                pieces,
+                &self.geometry.shifts_u64,
+                directions,
+                count,
+            )),
+            Squares::U128(pieces) => AnyBitboard::U128(Bitboard::new(
+                pieces,
+                &self.geometry.shifts_u128,
+                directions,
+                count,
+            )),
+            Squares::Each(_) => return None,
+        })
+    }
+
+    #[inline]
+    fn direction_index(&self, direction: (usize, usize)) -> Option<usize> {
+        self.movement
+            .directions()
+            .iter()
+            .position(|d| *d == direction)
+    }
+
+    /// Starts keeping the first layer of `network` up to date with this board, so evaluating
+    /// with it only has to run the small output layer. Fails if the network was made for
+    /// boards of another size.
+    pub fn attach_network(&mut self, network: Arc<Network>) -> Result<(), CheckersError> {
+        self.accumulator = Some(Accumulator::new(network, self)?);
+        Ok(())
+    }
+
+    pub fn accumulator(&self) -> Option<&Accumulator> {
+        self.accumulator.as_ref()
+    }
+
+    #[inline]
+    fn update_accumulator(&mut self, position: usize, old: Option<Piece>, new: Option<Piece>) {
+        if old == new {
+            return;
+        }
+        if let Some(accumulator) = self.accumulator.as_mut() {
+            if let Some(old) = old {
+                accumulator.remove(position, old);
+            }
+            if let Some(new) = new {
+                accumulator.add(position, new);
+            }
+        }
     }
 
     #[inline]
     pub fn is_within_bounds(&self, coord: (usize, usize)) -> bool {
         let (y, x) = coord;
-        y < self.size && x < self.size
+        y < self.rows && x < self.size
+    }
+
+    /// The position one step from `position` in `direction`, if it is on the board.
+    #[inline]
+    pub fn neighbor(&self, position: usize, direction: (usize, usize)) -> Option<usize> {
+        self.direction_index(direction)
+            .and_then(|i| self.geometry.neighbors[position][i])
+    }
+
+    /// Where a piece on `position` lands jumping over its neighbor in `direction`, if that is
+    /// on the board.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kar_checkers_minmax::board::Board;
+    /// let board = Board::new(8).unwrap();
+    /// assert_eq!(board.neighbor(9, (1, 1)), Some(14));
+    /// assert_eq!(board.jump(9, (1, 1)), Some(18));
+    /// assert_eq!(board.jump(5, (!0, !0)), None);
+    /// ```
+    #[inline]
+    pub fn jump(&self, position: usize, direction: (usize, usize)) -> Option<usize> {
+        self.direction_index(direction)
+            .and_then(|i| self.geometry.jumps[position][i])
     }
 
     pub fn get_player_piece_positions(&self, player: Player) -> impl Iterator<Item = usize> + '_ {
-        self.board.iter().enumerate().filter_map(move |(i, p)| {
-            if p.and_then(|p| Some(p.player() == player)).unwrap_or(false) {
-                Some(i)
-            } else {
-                None
-            }
-        })
+        let (bits, squares) = match &self.squares {
+            Squares::U32(pieces) => (u128::from(pieces.of(player)), None),
+            Squares::U64(pieces) => (u128::from(pieces.of(player)), None),
+            Squares::U128(pieces) => (pieces.of(player), None),
+            Squares::Each(squares) => (0, Some(squares)),
+        };
+        positions(bits).chain(squares.into_iter().flat_map(move |squares| {
+            squares
+                .iter()
+                .enumerate()
+                .filter(move |(_, p)| p.is_some_and(|p| p.player() == player))
+                .map(|(i, _)| i)
+        }))
     }
 
+    /// Encodes the board as one character per position: `r`/`w` for men, `R`/`W` for kings and
+    /// `.` for empty positions, in position order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kar_checkers_minmax::board::Board;
+    /// let board = Board::new(8).unwrap();
+    /// assert_eq!(board.encode(), "rrrrrrrrrrrr........wwwwwwwwwwww");
+    /// assert_eq!(Board::decode(&board.encode()), Ok(board));
+    /// ```
+    pub fn encode(&self) -> String {
+        (0..self.position_count)
+            .map(
+                |pos| match self.get(pos).map(|p| (p.player(), p.is_king())) {
+                    None => '.',
+                    Some((Player::Red, false)) => 'r',
+                    Some((Player::Red, true)) => 'R',
+                    Some((Player::White, false)) => 'w',
+                    Some((Player::White, true)) => 'W',
+                },
+            )
+            .collect()
+    }
+
+    /// Reverses [`Board::encode`], the board size is taken from the length of the string. No
+    /// length fits both a dark-square and an every-square board, so the movement is too.
+    pub fn decode(s: &str) -> Result<Self, CheckersError> {
+        let position_count = s.chars().count();
+        let board_size = (6..=position_count)
+            .step_by(2)
+            .find(|n| n * n == position_count * 2 || n * n == position_count)
+            .ok_or_else(|| {
+                CheckersError::MalformedPosition(format!(
+                    "{} positions do not fill a square board",
+                    position_count
+                ))
+            })?;
+        let board = s
+            .chars()
+            .map(|c| match c {
+                '.' => Some(None),
+                'r' => Some(Some(Piece::red())),
+                'w' => Some(Some(Piece::white())),
+                'R' | 'W' => {
+                    let mut piece = if c == 'R' {
+                        Piece::red()
+                    } else {
+                        Piece::white()
+                    };
+                    piece.to_king();
+                    Some(Some(piece))
+                }
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| CheckersError::MalformedPosition(format!("unknown piece in {:?}", s)))?;
+        let movement = if board_size * board_size == position_count {
+            Movement::Orthogonal
+        } else {
+            Movement::Diagonal
+        };
+        Self::with_layout(board_size, movement, board)
+    }
+
+    /// # Panics
+    ///
+    /// If `position` is not on the board, like [`Board::get`].
+    #[inline]
     pub fn to_coord(&self, position: usize) -> (usize, usize) {
-        let row = position / self.half_size;
-        (row, position % self.half_size * 2 + (row + 1) % 2)
+        self.geometry.coords[position]
     }
 
     pub fn to_position(&self, coord: (usize, usize)) -> usize {
         let (row, col) = coord;
-        row * self.half_size + col / 2
+        match self.movement {
+            Movement::Diagonal => row * self.half_size + col / 2,
+            Movement::Orthogonal => row * self.size + col,
+        }
     }
 }
 
 impl Default for Board {
     fn default() -> Self {
-        Self::new(8)
+        Self::new(8).expect("8x8 boards are valid")
+    }
+}
+
+/// Shows the pieces as [`Board::encode`] does, leaving out the shared tables and the network.
+impl Debug for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Board")
+            .field("size", &self.size)
+            .field("rows", &self.rows)
+            .field("movement", &self.movement)
+            .field("pieces", &self.encode())
+            .field("network_attached", &self.accumulator.is_some())
+            .finish()
     }
 }
 
 impl Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let with_squares = self
-            .board
-            .iter()
+        let pieces = (0..self.position_count).map(|pos| self.get(pos));
+        if self.movement == Movement::Orthogonal {
+            let cells = pieces
+                .map(|p| p.map(|p| format!("{}", p)).unwrap_or_default())
+                .collect::<Vec<String>>();
+            return write!(f, "{}", IterTable::new(cells.chunks(self.size)).to_string());
+        }
+        let with_squares = pieces
             .zip(repeat("".to_owned()))
             .enumerate()
             .flat_map(|(i, (p, sq))| {
-                if (i / self.half_size) % 2 == 0 {
-                    vec![
-                        sq,
-                        p.and_then(|p| Some(format!("{}", p)))
-                            .unwrap_or("".to_owned()),
-                    ]
+                if (i / self.half_size).is_multiple_of(2) {
+                    vec![sq, p.map(|p| format!("{}", p)).unwrap_or_default()]
                 } else {
-                    vec![
-                        p.and_then(|p| Some(format!("{}", p)))
-                            .unwrap_or("".to_owned()),
-                        sq,
-                    ]
+                    vec![p.map(|p| format!("{}", p)).unwrap_or_default(), sq]
                 }
             })
             .collect::<Vec<String>>();
-        let rows = with_squares.chunks(self.size).map(|strings| strings);
+        let rows = with_squares.chunks(self.size);
         write!(f, "{}", IterTable::new(rows).to_string())
     }
 }
 
 impl PartialEq for Board {
     fn eq(&self, other: &Self) -> bool {
-        self.size == other.size && self.board == other.board
+        self.size == other.size && self.rows == other.rows && self.squares == other.squares
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.rows.hash(state);
+        self.squares.hash(state);
     }
 }
 
@@ -200,7 +686,7 @@ mod tests {
 
     #[test]
     fn test_to_coords() {
-        let board = Board::new(8);
+        let board = Board::new(8).unwrap();
 
         assert_eq!(board.to_coord(0), (0, 1));
         assert_eq!(board.to_coord(3), (0, 7));
@@ -212,7 +698,7 @@ mod tests {
 
     #[test]
     fn test_to_position() {
-        let board = Board::new(8);
+        let board = Board::new(8).unwrap();
 
         assert_eq!(board.to_position((0, 1)), 0);
         assert_eq!(board.to_position((0, 7)), 3);
@@ -221,4 +707,158 @@ mod tests {
         assert_eq!(board.to_position((6, 5)), 26);
         assert_eq!(board.to_position((7, 6)), 31);
     }
+
+    #[test]
+    fn test_start_layout_for_every_size() {
+        for (size, rows, men) in [(6, 2, 6), (8, 3, 12), (10, 4, 20), (12, 5, 30)] {
+            let board = Board::new(size).unwrap();
+            assert_eq!(Board::default_starting_rows(size), rows);
+            for player in [Player::Red, Player::White] {
+                assert_eq!(board.get_player_piece_positions(player).count(), men);
+            }
+            // the two middle rows are empty
+            let middle = (size / 2 - 1) * size / 2..(size / 2 + 1) * size / 2;
+            assert!(middle.clone().all(|pos| board.get(pos).is_none()));
+            assert!(board.get(middle.start - 1).is_some());
+            assert!(board.get(middle.end).is_some());
+        }
+    }
+
+    #[test]
+    fn test_rectangular_board() {
+        let board = Board::rectangular(8, 10, 3).unwrap();
+
+        assert_eq!((board.size(), board.rows()), (8, 10));
+        assert_eq!(board.position_count(), 40);
+        assert_eq!(board.to_coord(39), (9, 6));
+        assert!(board.is_within_bounds((9, 7)));
+        assert!(!board.is_within_bounds((8, 8)));
+        assert_eq!(board.get(11), Some(Piece::red()));
+        assert_eq!(board.get(12), None);
+        assert_eq!(board.get(28), Some(Piece::white()));
+        assert_ne!(board, Board::rectangular(8, 8, 3).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_boards() {
+        assert_eq!(Board::new(7), Err(CheckersError::InvalidBoardSize(7)));
+        assert_eq!(Board::new(4), Err(CheckersError::InvalidBoardSize(4)));
+        assert!(matches!(
+            Board::rectangular(8, 6, 3),
+            Err(CheckersError::MalformedPosition(_))
+        ));
+        assert!(matches!(
+            Board::with_layout(8, Movement::Diagonal, vec![None; 34]),
+            Err(CheckersError::MalformedPosition(_))
+        ));
+        assert!(matches!(
+            Board::decode("rrrrrrrrrrrr........wwwwwwwwwww"),
+            Err(CheckersError::MalformedPosition(_))
+        ));
+        assert!(matches!(
+            Board::decode("rrrrrrrrrrrr....x...wwwwwwwwwwww"),
+            Err(CheckersError::MalformedPosition(_))
+        ));
+    }
+
+    #[test]
+    fn test_tables_match_coordinates() {
+        fn shifted<B: Bits>(
+            bits: Bitboard<B>,
+            position: usize,
+            direction: (usize, usize),
+        ) -> Option<usize> {
+            positions(bits.shift(B::bit(position), direction)).next()
+        }
+
+        let boards = [
+            Board::new(6).unwrap(),
+            Board::new(8).unwrap(),
+            Board::new(16).unwrap(),
+            Board::new(18).unwrap(),
+            Board::rectangular(8, 10, 3).unwrap(),
+            Board::for_rules(&Ruleset::turkish()).unwrap(),
+        ];
+        for board in boards {
+            for direction in board.movement().directions() {
+                for position in 0..board.position_count() {
+                    let (row, col) = board.to_coord(position);
+                    let coord = (row.wrapping_add(direction.0), col.wrapping_add(direction.1));
+                    let expected = board
+                        .is_within_bounds(coord)
+                        .then(|| board.to_position(coord));
+                    assert_eq!(board.neighbor(position, *direction), expected);
+                    assert_eq!(
+                        board.jump(position, *direction),
+                        expected.and_then(|p| board.neighbor(p, *direction))
+                    );
+                    let shifted = match board.bitboard() {
+                        Some(AnyBitboard::U32(bits)) => shifted(bits, position, *direction),
+                        Some(AnyBitboard::U64(bits)) => shifted(bits, position, *direction),
+                        Some(AnyBitboard::U128(bits)) => shifted(bits, position, *direction),
+                        None => expected,
+                    };
+                    assert_eq!(shifted, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_debug_shows_pieces() {
+        let board = Board::new(6).unwrap();
+        assert_eq!(
+            format!("{:?}", board),
+            "Board { size: 6, rows: 6, movement: Diagonal, pieces: \"rrrrrr......wwwwww\", \
+             network_attached: false }"
+        );
+    }
+
+    #[test]
+    fn test_storage_fits_board_size() {
+        let width = |board: Board| match board.bitboard() {
+            Some(AnyBitboard::U32(_)) => Some(32),
+            Some(AnyBitboard::U64(_)) => Some(64),
+            Some(AnyBitboard::U128(_)) => Some(128),
+            None => None,
+        };
+        assert_eq!(width(Board::new(8).unwrap()), Some(32));
+        assert_eq!(width(Board::new(10).unwrap()), Some(64));
+        assert_eq!(
+            width(Board::for_rules(&Ruleset::turkish()).unwrap()),
+            Some(64)
+        );
+        assert_eq!(width(Board::new(12).unwrap()), Some(128));
+        assert_eq!(width(Board::new(18).unwrap()), None);
+
+        let mut board = Board::new(18).unwrap();
+        assert_eq!(board.get_player_piece_positions(Player::White).count(), 72);
+        board.r#move(71, 80);
+        board.crown(80);
+        assert!(board.get(80).is_some_and(|p| p.is_king()));
+        assert_eq!(Board::decode(&board.encode()), Ok(board));
+    }
+
+    #[test]
+    fn test_orthogonal_coords() {
+        let board = Board::for_rules(&Ruleset::turkish()).unwrap();
+
+        assert_eq!(board.to_coord(0), (0, 0));
+        assert_eq!(board.to_coord(9), (1, 1));
+        assert_eq!(board.to_coord(63), (7, 7));
+        assert_eq!(board.to_position((2, 5)), 21);
+        assert_eq!(board.neighbor(7, (0, 1)), None);
+        assert_eq!(board.neighbor(7, (1, 0)), Some(15));
+        assert_eq!(Board::decode(&board.encode()), Ok(board));
+    }
+
+    #[test]
+    fn test_orthogonal_display_shows_every_square() {
+        let board = Board::for_rules(&Ruleset::turkish()).unwrap();
+        let shown = board.to_string();
+
+        assert_eq!(shown.matches("🔴").count(), 16);
+        assert_eq!(shown.matches("⚪").count(), 16);
+        assert_eq!(shown.lines().next().unwrap().matches('+').count(), 9);
+    }
 }
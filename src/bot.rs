@@ -2,7 +2,27 @@ use std::fmt::Debug;
 
 use crate::checkers::Checkers;
 
+/// What a bot makes of its position at the start of its turn.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Assessment {
+    PlayOn,
+    /// A draw would be welcome, so offer one or accept the opponent's.
+    WantsDraw,
+    Resign,
+}
+
 pub trait Bot: Debug {
-    fn get_next_move(&self, game: &Checkers, depth_limit: Option<usize>) -> usize;
-    fn get_next_move_with_display(&self, game: &Checkers, depth_limit: Option<usize>) -> usize;
+    /// The position to pass to [`Checkers::make_a_move_from_api`] next, `None` if the player to
+    /// move has no move.
+    fn get_next_move(&self, game: &Checkers, depth_limit: Option<usize>) -> Option<usize>;
+    fn get_next_move_with_display(
+        &self,
+        game: &Checkers,
+        depth_limit: Option<usize>,
+    ) -> Option<usize>;
+
+    /// Decides whether to resign or to offer or accept a draw before moving.
+    fn assess(&self, _game: &Checkers) -> Assessment {
+        Assessment::PlayOn
+    }
 }
@@ -1,4 +1,11 @@
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum BotChoice {
     MinMax,
+    /// A [`BotChoice::MinMax`] bot that scores draws as `-contempt`, where one piece is worth 100.
+    /// Use a positive value for a bot that should play for a win, negative for one that should
+    /// settle for a draw.
+    MinMaxWithContempt(i64),
+    /// A [`BotChoice::MinMax`] bot that evaluates positions with the neural network in
+    /// `network.nnue`, falling back to the linear evaluator when there is no network file.
+    MinMaxNnue,
 }
@@ -1,8 +1,31 @@
+use std::sync::{
+    mpsc::{self, Receiver},
+    Arc,
+};
+
 use text_io::try_read;
 
 use crate::{
-    board::Board, bot::Bot, bot_choice::BotChoice, history::History, io, minmax::MinMax,
-    piece::Piece, player::Player, r#move::Move,
+    bitboard::{positions, AnyBitboard, Bitboard, Bits},
+    board::Board,
+    bot::{Assessment, Bot},
+    bot_choice::BotChoice,
+    constants::{BOT_DRAW_OFFER_INTERVAL, DEFAULT_SEARCH_DEPTH, REPETITION_LIMIT},
+    error::{CheckersError, IllegalMove},
+    evaluation::{Evaluator, WEIGHTS_FILE},
+    event::{GameEvent, GameObserver},
+    history::History,
+    io,
+    minmax::MinMax,
+    nnue::{Network, NETWORK_FILE},
+    outcome::{DrawReason, GameAction, GameOutcome, WinReason},
+    piece::Piece,
+    player::Player,
+    r#move::Move,
+    ruleset::{
+        CapturePrecedence, CaptureRemoval, KingRange, Movement, Promotion, Ruleset, WinCondition,
+    },
+    turn::Turn,
 };
 
 #[derive(Default, Debug)]
@@ -14,52 +37,241 @@ pub struct Checkers {
     valid_moves: Vec<Move>,
     selectable_positions: Vec<usize>,
     history: History,
+    /// Board and player to move at the start of every turn so far, the current one included.
+    positions: Vec<(Board, Player)>,
+    rules: Ruleset,
+    /// Outcome decided by a player rather than by the position, such as a draw claim.
+    result: Option<GameOutcome>,
+    /// Take-backs each player may use, `None` for no limit.
+    take_back_limit: Option<usize>,
+    observers: Vec<Box<dyn GameObserver>>,
+    /// Prints events while [`Checkers::start`] runs, kept apart from `observers` so that only
+    /// it is dropped when the game ends.
+    #[cfg(feature = "standalone")]
+    printer: Option<TerminalPrinter>,
+}
+
+/// Prints game events in the terminal while [`Checkers::start`] runs.
+#[cfg(feature = "standalone")]
+#[derive(Debug)]
+struct TerminalPrinter;
+
+#[cfg(feature = "standalone")]
+impl GameObserver for TerminalPrinter {
+    fn notify(&mut self, event: &GameEvent, board: &Board) {
+        match event {
+            GameEvent::PieceSelected { position } => {
+                println!("\nPIECE SELECTED {:?}\n", board.to_coord(*position))
+            }
+            GameEvent::PieceMoved { from, to } => println!(
+                "PIECE MOVED {:?} -> {:?}\n",
+                board.to_coord(*from),
+                board.to_coord(*to)
+            ),
+            GameEvent::PieceCaptured { position, piece } if piece.is_king() => {
+                println!("\nCAPTURED KING PIECE {:?}", board.to_coord(*position))
+            }
+            GameEvent::PieceCaptured { position, .. } => {
+                println!("\nCAPTURED PIECE {:?}", board.to_coord(*position))
+            }
+            GameEvent::PieceHuffed { position, .. } => {
+                println!("\nHUFFED PIECE {:?}\n", board.to_coord(*position))
+            }
+            GameEvent::PieceCrowned { position } => {
+                println!("\nCROWNED {:?}\n", board.to_coord(*position))
+            }
+            GameEvent::StepUndone => println!("\nSTEP UNDONE\n"),
+            GameEvent::TurnChanged { player } => println!("TURN CHANGE\n{} TURN:", player),
+            GameEvent::GameOver(outcome) => println!("!!! {} !!!", outcome),
+        }
+    }
 }
 
 impl Clone for Checkers {
     fn clone(&self) -> Self {
         Self {
             board: self.board.clone(),
-            current_player: self.current_player.clone(),
+            current_player: self.current_player,
             bot_player: None,
-            selected_piece: self.selected_piece.clone(),
+            selected_piece: self.selected_piece,
             valid_moves: self.valid_moves.clone(),
             selectable_positions: self.selectable_positions.clone(),
             history: self.history.clone(),
+            positions: self.positions.clone(),
+            rules: self.rules,
+            result: self.result,
+            take_back_limit: self.take_back_limit,
+            observers: Vec::new(),
+            #[cfg(feature = "standalone")]
+            printer: None,
         }
     }
 }
 
 impl Checkers {
     pub fn new(bot_player: Option<(Player, BotChoice)>) -> Self {
-        let bot_player = if let Some((player, bot_choice)) = bot_player {
-            let bot: Box<dyn Bot> = match bot_choice {
-                BotChoice::MinMax => Box::new(MinMax),
-            };
-            Some((player, bot))
-        } else {
-            None
+        Self::with_rules(Ruleset::default(), bot_player).expect("the default rules are valid")
+    }
+
+    pub fn custom_board(
+        board_size: usize,
+        bot_player: Option<(Player, BotChoice)>,
+    ) -> Result<Self, CheckersError> {
+        let rules = Ruleset {
+            board_size,
+            starting_rows: Board::default_starting_rows(board_size),
+            ..Default::default()
         };
-        Self {
-            bot_player,
+        Self::with_rules(rules, bot_player)
+    }
+
+    /// A game played by `rules`, which also set the board size and the player who moves first.
+    pub fn with_rules(
+        rules: Ruleset,
+        bot_player: Option<(Player, BotChoice)>,
+    ) -> Result<Self, CheckersError> {
+        let mut game = Self {
+            board: Board::for_rules(&rules)?,
+            current_player: rules.first_player,
+            bot_player: Self::create_bot(bot_player),
+            rules,
             ..Default::default()
+        };
+        game.start_headless();
+        Ok(game)
+    }
+
+    /// Allows each player `limit` take-backs in the game, see [`Checkers::take_back`].
+    pub fn with_take_back_limit(mut self, limit: Option<usize>) -> Self {
+        self.take_back_limit = limit;
+        self
+    }
+
+    /// A copy to play turns on and take them back freely while searching or counting, without
+    /// the take-back limit of the game.
+    pub(crate) fn analysis_copy(&self) -> Self {
+        self.clone().with_take_back_limit(None)
+    }
+
+    /// Bots use the weights in [`WEIGHTS_FILE`] when it exists, otherwise the default weights.
+    /// [`BotChoice::MinMaxNnue`] bots use the network in [`NETWORK_FILE`] if it can be loaded, on
+    /// boards it fits.
+    fn create_bot(bot_player: Option<(Player, BotChoice)>) -> Option<(Player, Box<dyn Bot>)> {
+        let (player, bot_choice) = bot_player?;
+        let evaluator = Evaluator::load(WEIGHTS_FILE).unwrap_or_default();
+        let bot: Box<dyn Bot> = match bot_choice {
+            BotChoice::MinMax => Box::new(MinMax::new(evaluator, 0)),
+            BotChoice::MinMaxWithContempt(contempt) => Box::new(MinMax::new(evaluator, contempt)),
+            BotChoice::MinMaxNnue => {
+                let bot = MinMax::new(evaluator, 0);
+                match Network::load(NETWORK_FILE) {
+                    Ok(network) => Box::new(bot.with_network(Arc::new(network))),
+                    Err(_) => Box::new(bot),
+                }
+            }
+        };
+        Some((player, bot))
+    }
+
+    /// The default rules resized to the square `board`, or the turkish rules for orthogonal
+    /// moves.
+    fn rules_for(board: &Board) -> Ruleset {
+        let starting_rows = Board::default_starting_rows(board.rows());
+        let variant = match board.movement() {
+            Movement::Diagonal => Ruleset {
+                starting_rows,
+                ..Default::default()
+            },
+            // turkish men leave their back row empty
+            Movement::Orthogonal => Ruleset {
+                starting_rows: starting_rows.saturating_sub(1),
+                ..Ruleset::turkish()
+            },
+        };
+        Ruleset {
+            board_size: board.size(),
+            ..variant
         }
     }
 
-    pub fn custom_board(board_size: usize, bot_player: Option<(Player, BotChoice)>) -> Self {
-        let bot_player = if let Some((player, bot_choice)) = bot_player {
-            let bot: Box<dyn Bot> = match bot_choice {
-                BotChoice::MinMax => Box::new(MinMax),
-            };
-            Some((player, bot))
-        } else {
-            None
+    /// A game with `side_to_move` to move in the position on `board`, played by `rules` or, if
+    /// there are none, by the default rules resized to the board, or the turkish rules on a
+    /// board for orthogonal moves. Without rules the board has to be square, as only rules can
+    /// say a board is meant to have another number of rows. The position is checked to fit the
+    /// rules: the board has to have their size, no man may stand on the row it would be crowned
+    /// on, and neither player may have more pieces than they start with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kar_checkers_minmax::{board::Board, checkers::Checkers, player::Player};
+    /// let board = Board::decode("W....................r.....r..R.")?;
+    /// let game = Checkers::setup(board, Player::Red, None)?;
+    /// assert_eq!(game.side_to_move(), Player::Red);
+    /// let crowded = Board::decode(&"r".repeat(32))?;
+    /// assert!(Checkers::setup(crowded, Player::Red, None).is_err());
+    /// # Ok::<(), kar_checkers_minmax::error::CheckersError>(())
+    /// ```
+    pub fn setup(
+        board: Board,
+        side_to_move: Player,
+        rules: Option<Ruleset>,
+    ) -> Result<Self, CheckersError> {
+        let rules = match rules {
+            Some(rules) => rules,
+            None if board.rows() != board.size() => {
+                return Err(CheckersError::MalformedPosition(format!(
+                    "a {}x{} board is not square, rectangular boards need rules",
+                    board.size(),
+                    board.rows()
+                )));
+            }
+            None => Self::rules_for(&board),
         };
-        Self {
-            board: Board::new(board_size),
-            bot_player,
+        let start = Board::for_rules(&rules)?;
+        if (board.size(), board.rows(), board.movement())
+            != (start.size(), start.rows(), start.movement())
+        {
+            return Err(CheckersError::MalformedPosition(format!(
+                "a {}x{} {:?} board does not fit rules for a {}x{} {:?} board",
+                board.size(),
+                board.rows(),
+                board.movement(),
+                start.size(),
+                start.rows(),
+                start.movement()
+            )));
+        }
+        let mut game = Self {
+            board,
+            current_player: side_to_move,
+            rules,
             ..Default::default()
+        };
+        for player in [Player::Red, Player::White] {
+            let pieces = game.board.get_player_piece_positions(player).count();
+            let most = start.get_player_piece_positions(player).count();
+            if pieces > most {
+                return Err(CheckersError::MalformedPosition(format!(
+                    "{} has {} pieces, more than the {} it starts with",
+                    player, pieces, most
+                )));
+            }
+        }
+        for pos in 0..game.board.position_count() {
+            let Some(piece) = game.board.get(pos) else {
+                continue;
+            };
+            if !piece.is_king() && game.board.to_coord(pos).0 == game.end_row_for_piece(&piece) {
+                return Err(CheckersError::MalformedPosition(format!(
+                    "{} man on position {} should have been crowned",
+                    piece.player(),
+                    pos
+                )));
+            }
         }
+        game.start_headless();
+        Ok(game)
     }
 
     #[cfg(feature = "standalone")]
@@ -67,59 +279,308 @@ impl Checkers {
         println!("{}", self.board);
     }
 
-    pub fn start(&mut self) {
+    /// Prepares the first turn without starting the terminal game loop, for games driven
+    /// through [`Checkers::make_a_move_from_api`].
+    pub fn start_headless(&mut self) {
+        if self.positions.is_empty() {
+            self.positions
+                .push((self.board.clone(), self.current_player));
+        }
         self.update_valid_moves();
-        loop {
+    }
+
+    /// Tells `observer` about everything that happens in the game from now on. Copies of the
+    /// game, such as those bots search with, have no observers.
+    pub fn add_observer(&mut self, observer: Box<dyn GameObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// A channel receiving everything that happens in the game from now on.
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.add_observer(Box::new(sender));
+        receiver
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        for observer in self.observers.iter_mut() {
+            observer.notify(&event, &self.board);
+        }
+        #[cfg(feature = "standalone")]
+        if let Some(printer) = &mut self.printer {
+            printer.notify(&event, &self.board);
+        }
+    }
+
+    fn is_observed(&self) -> bool {
+        #[cfg(feature = "standalone")]
+        if self.printer.is_some() {
+            return true;
+        }
+        !self.observers.is_empty()
+    }
+
+    /// Plays the game in the terminal until it is over.
+    pub fn start(&mut self) -> GameOutcome {
+        #[cfg(feature = "standalone")]
+        {
+            self.printer = Some(TerminalPrinter);
+        }
+        self.start_headless();
+        if let Some(outcome) = self.outcome() {
+            self.emit(GameEvent::GameOver(outcome));
+        }
+        let outcome = loop {
+            if let Some(outcome) = self.outcome() {
+                break outcome;
+            }
+
             #[cfg(feature = "standalone")]
             self.show_board();
 
-            if self
-                .bot_player
-                .as_ref()
-                .and_then(|p| Some(p.0 == self.current_player))
-                .unwrap_or(false)
-            {
-                let bot_move = self
-                    .bot_player
-                    .as_ref()
-                    .unwrap()
-                    .1
-                    .get_next_move_with_display(&self, None);
-                self.make_a_move_from_api(bot_move);
+            let player = self.current_player;
+            if self.bot_player.as_ref().is_some_and(|p| p.0 == player) {
+                let assessment = self.bot_player.as_ref().unwrap().1.assess(self);
+                self.act_on_assessment(assessment);
+                while self.current_player == player && self.result.is_none() {
+                    let Some(bot_move) = self
+                        .bot_player
+                        .as_ref()
+                        .unwrap()
+                        .1
+                        .get_next_move_with_display(self, Some(DEFAULT_SEARCH_DEPTH))
+                    else {
+                        break;
+                    };
+                    self.make_a_move_from_api(bot_move)
+                        .expect("bots only pick selectable positions");
+                }
             } else {
-                while self.can_move() {
+                // undoing hands the turn back to the player who played the last one
+                while self.current_player == player && self.can_move() && self.result.is_none() {
                     self.make_a_move_from_terminal();
                 }
+                if self.current_player == player && self.result.is_none() {
+                    self.end_turn();
+                }
             }
+        };
+        #[cfg(feature = "standalone")]
+        {
+            self.printer = None;
+        }
+        outcome
+    }
 
-            self.end_turn();
-            self.announce_new_turn();
-            if !self.can_move() {
-                #[cfg(feature = "standalone")]
-                self.announce_winner(Some(self.current_player.other()));
-                break;
+    /// How the game ended, or `None` while it goes on.
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        if self.result.is_some() {
+            return self.result;
+        }
+        if let Some(winner) = self.winner() {
+            let reason = match self.rules.win_condition {
+                WinCondition::Standard => WinReason::NoMoves,
+                WinCondition::Giveaway => WinReason::GaveAway,
+            };
+            return Some(GameOutcome::Win { winner, reason });
+        }
+        if !self.at_turn_start() {
+            None
+        } else if self.position_repetitions() >= REPETITION_LIMIT {
+            Some(GameOutcome::Draw(DrawReason::Repetition))
+        } else if self
+            .rules
+            .quiet_turn_limit
+            .is_some_and(|limit| self.quiet_turns() >= limit)
+        {
+            Some(GameOutcome::Draw(DrawReason::MoveLimit))
+        } else {
+            None
+        }
+    }
+
+    /// Finished turns in a row, counting back from the last one, without a capture, a huff or
+    /// a man moving.
+    pub fn quiet_turns(&self) -> usize {
+        self.history
+            .turns
+            .iter()
+            .rev()
+            .skip_while(|(player, _)| *player == self.current_player)
+            .take_while(|(_, moves)| {
+                moves.iter().all(|m| !m.is_capture() && !m.is_huff())
+                    && moves.first().is_some_and(|m| m.started_king())
+            })
+            .count()
+    }
+
+    /// Ends the game in a draw if the rules let the player to move claim one, returning
+    /// whether the claim was accepted.
+    pub fn claim_draw(&mut self) -> bool {
+        let allowed = self.result.is_none()
+            && self.at_turn_start()
+            && self
+                .rules
+                .quiet_turn_claim
+                .is_some_and(|claim| self.quiet_turns() >= claim);
+        if allowed {
+            self.finish(GameOutcome::Draw(DrawReason::InsufficientProgress));
+        }
+        allowed
+    }
+
+    /// Resigns the game for the player to move.
+    pub fn resign(&mut self) {
+        if self.outcome().is_some() {
+            return;
+        }
+        let player = self.current_player;
+        self.record_action(GameAction::Resign(player));
+        self.finish(GameOutcome::Win {
+            winner: player.other(),
+            reason: WinReason::Resignation,
+        });
+    }
+
+    /// Offers the opponent a draw, which they can accept at the start of their next turn.
+    /// Returns whether the offer was made, a player can only offer once per turn.
+    pub fn offer_draw(&mut self) -> bool {
+        let offer = GameAction::OfferDraw(self.current_player);
+        if self.outcome().is_some()
+            || self.history.last_action() == Some(&(self.turns_played(), offer))
+        {
+            return false;
+        }
+        self.record_action(offer);
+        true
+    }
+
+    /// The opponent, if they offered a draw on their last turn that the player to move has not
+    /// answered yet. Moving declines the offer, as [`Checkers::decline_draw`] does.
+    pub fn draw_offer(&self) -> Option<Player> {
+        match self.history.last_action() {
+            Some((turn, GameAction::OfferDraw(player)))
+                if *player != self.current_player
+                    && turn + 1 == self.turns_played()
+                    && self.at_turn_start()
+                    && self.result.is_none() =>
+            {
+                Some(*player)
             }
+            _ => None,
         }
     }
 
-    #[cfg(feature = "standalone")]
-    fn announce_winner(&mut self, winner: Option<Player>) {
-        if let Some(winner) = winner {
-            println!("!!! {} PLAYER WINS !!!", winner);
-        } else {
-            println!("Game over, nobody wins.");
+    /// Accepts the opponent's draw offer, returning whether there was one.
+    pub fn accept_draw(&mut self) -> bool {
+        if self.draw_offer().is_none() {
+            return false;
+        }
+        self.record_action(GameAction::AcceptDraw(self.current_player));
+        self.finish(GameOutcome::Draw(DrawReason::Agreement));
+        true
+    }
+
+    /// Declines the opponent's draw offer, returning whether there was one.
+    pub fn decline_draw(&mut self) -> bool {
+        if self.draw_offer().is_none() {
+            return false;
+        }
+        self.record_action(GameAction::DeclineDraw(self.current_player));
+        true
+    }
+
+    /// Ends the game early, won by `winner` or drawn if `None`, for games between bots that
+    /// are stopped by the program running them.
+    pub fn adjudicate(&mut self, winner: Option<Player>) {
+        if self.outcome().is_some() {
+            return;
+        }
+        let outcome = match winner {
+            Some(winner) => GameOutcome::Win {
+                winner,
+                reason: WinReason::Adjudication,
+            },
+            None => GameOutcome::Draw(DrawReason::Adjudication),
+        };
+        self.record_action(GameAction::Adjudicate(outcome));
+        self.finish(outcome);
+    }
+
+    /// Ends the game with an outcome decided by a player or by the program running it.
+    fn finish(&mut self, outcome: GameOutcome) {
+        self.result = Some(outcome);
+        self.emit(GameEvent::GameOver(outcome));
+    }
+
+    /// Acts on a bot's [`Assessment`] of the position at the start of its turn: answers a draw
+    /// offer, resigns, or offers a draw if it has not offered one recently.
+    pub(crate) fn act_on_assessment(&mut self, assessment: Assessment) {
+        if self.draw_offer().is_some() {
+            if assessment == Assessment::WantsDraw && self.accept_draw() {
+                return;
+            }
+            self.decline_draw();
+        }
+        match assessment {
+            Assessment::PlayOn => {}
+            Assessment::Resign => self.resign(),
+            Assessment::WantsDraw => {
+                let player = self.current_player;
+                let offered_recently = self.history.actions.iter().any(|(turn, action)| {
+                    *action == GameAction::OfferDraw(player)
+                        && turn + BOT_DRAW_OFFER_INTERVAL > self.turns_played()
+                });
+                if !offered_recently {
+                    self.offer_draw();
+                }
+            }
         }
     }
 
+    fn record_action(&mut self, action: GameAction) {
+        self.history.record_action(self.turns_played(), action);
+    }
+
+    fn at_turn_start(&self) -> bool {
+        self.selected_piece.is_none() && self.history.get_last_player() != Some(self.current_player)
+    }
+
     fn end_turn(&mut self) {
         self.selected_piece = None;
         self.current_player = self.current_player.other();
+        self.positions
+            .push((self.board.clone(), self.current_player));
         self.update_valid_moves();
+        if self.is_observed() {
+            self.emit(GameEvent::TurnChanged {
+                player: self.current_player,
+            });
+            if let Some(outcome) = self.outcome() {
+                self.emit(GameEvent::GameOver(outcome));
+            }
+        }
     }
 
-    #[cfg(feature = "standalone")]
-    fn announce_new_turn(&self) {
-        println!("TURN CHANGE\n{} TURN:", self.current_player);
+    /// The winner once the player to move has no moves left, which depends on the
+    /// [`WinCondition`] of the rules. `None` while the game goes on.
+    pub fn winner(&self) -> Option<Player> {
+        if self.can_move() || self.selected_piece.is_some() {
+            return None;
+        }
+        Some(match self.rules.win_condition {
+            WinCondition::Standard => self.current_player.other(),
+            WinCondition::Giveaway => self.current_player,
+        })
+    }
+
+    /// How many times the current position, with the current player to move, has occurred at
+    /// the start of a turn, the start of the game included.
+    pub fn position_repetitions(&self) -> usize {
+        self.positions
+            .iter()
+            .filter(|(board, player)| *player == self.current_player && *board == self.board)
+            .count()
     }
 
     fn update_valid_moves(&mut self) {
@@ -143,6 +604,63 @@ impl Checkers {
             self.valid_moves.iter().map(|m| m.start()).collect()
         };
         self.selectable_positions.dedup();
+        if self.selected_piece.is_none() && !self.valid_moves.is_empty() {
+            let huffable = self.huffable_positions();
+            self.selectable_positions.extend(huffable);
+        }
+    }
+
+    /// Positions of the opponent's pieces that could have captured on the opponent's last
+    /// turn but did not, which the current player may huff before moving. Only the piece that
+    /// moved has changed position since then, so the others are found where they stood. There
+    /// is nothing to huff when captures are mandatory.
+    fn huffable_positions(&self) -> Vec<usize> {
+        let opponent = self.current_player.other();
+        if !self.rules.huffing
+            || self.rules.mandatory_capture
+            || self.history.get_last_player() != Some(opponent)
+        {
+            return Vec::new();
+        }
+        let Some(turn) = self.history.get_last_turn() else {
+            return Vec::new();
+        };
+        if turn.iter().any(|m| m.is_capture()) {
+            return Vec::new();
+        }
+        let (Some(first), Some(last)) = (turn.iter().find(|m| !m.is_huff()), turn.last()) else {
+            return Vec::new();
+        };
+
+        // the board at the start of the opponent's turn proper, with their move taken back, a
+        // piece they huffed before it stays off the board
+        let mut before = self.board.clone();
+        if let Some(mut piece) = before.take(last.end()) {
+            if !first.started_king() {
+                piece.remove_king();
+            }
+            before.set(first.start(), Some(piece));
+        }
+        let game = Self {
+            board: before,
+            current_player: opponent,
+            rules: self.rules,
+            ..Default::default()
+        };
+        let mut positions: Vec<usize> = game
+            .capture_sequences_for_player()
+            .into_iter()
+            .filter_map(|s| s.first().map(|m| m.start()))
+            .map(|pos| {
+                if pos == first.start() {
+                    last.end()
+                } else {
+                    pos
+                }
+            })
+            .collect();
+        positions.dedup();
+        positions
     }
 
     fn can_move(&self) -> bool {
@@ -155,19 +673,120 @@ impl Checkers {
             .get_last_player()
             .unwrap_or(self.current_player.other())
             != self.current_player;
-        if !is_first_move_this_turn && !self.history.last_move_was_capture() {
+        // huffing a piece is done before the turn proper, which still starts afterwards
+        let at_turn_start = is_first_move_this_turn || self.history.last_move_was_huff();
+        if !at_turn_start
+            && !self.history.last_move_was_capture()
+            && !self.history.last_move_was_select()
+        {
             return Vec::new();
         }
 
-        let all_moves = self.all_moves_for_player(self.current_player);
-        let must_cap = self.selected_piece.is_some()
-            && (is_first_move_this_turn || self.history.last_move_was_capture())
-            || all_moves.iter().any(|m| m.is_capture());
-        if must_cap {
-            all_moves.into_iter().filter(|m| m.is_capture()).collect()
+        let continuing = !at_turn_start && self.history.last_move_was_capture();
+        let captures = if continuing {
+            self.capture_continuations()
+        } else {
+            self.capture_sequences_for_player()
+        };
+        let mut moves: Vec<Move> = Vec::new();
+        for m in captures.into_iter().filter_map(|s| s.into_iter().next()) {
+            if !moves.contains(&m) {
+                moves.push(m);
+            }
+        }
+        if !continuing && (moves.is_empty() || !self.rules.mandatory_capture) {
+            moves.extend(self.plain_moves());
+        }
+        moves
+    }
+
+    /// Every move without a capture for the current player, in the order of the positions of
+    /// the pieces making them.
+    fn plain_moves(&self) -> Vec<Move> {
+        match self.board.bitboard() {
+            Some(AnyBitboard::U32(bits)) => self.shifted_plain_moves(bits),
+            Some(AnyBitboard::U64(bits)) => self.shifted_plain_moves(bits),
+            Some(AnyBitboard::U128(bits)) => self.shifted_plain_moves(bits),
+            None => self
+                .board
+                .get_player_piece_positions(self.current_player)
+                .flat_map(|pos| self.plain_moves_for_pos(pos))
+                .collect(),
+        }
+    }
+
+    /// [`Checkers::plain_moves`] with the pieces that move a single step moved all at once by
+    /// shifting bitboards, only flying kings are followed square by square.
+    fn shifted_plain_moves<B: Bits>(&self, bits: Bitboard<B>) -> Vec<Move> {
+        let board = &self.board;
+        let own = bits.pieces(self.current_player);
+        let kings = own & bits.kings();
+        let flying = self.rules.king_range == KingRange::Flying;
+        let man = match self.current_player {
+            Player::Red => Piece::red(),
+            Player::White => Piece::white(),
+        };
+        let end_row = self.end_row_for_piece(&man);
+
+        let mut moves = Vec::new();
+        for direction in board.movement().directions() {
+            let mut movers = if flying { own & !kings } else { own };
+            if !man.directions(board.movement()).contains(direction) {
+                movers = movers & kings;
+            }
+            let back = (direction.0.wrapping_neg(), direction.1.wrapping_neg());
+            for end in positions(bits.shift(movers, *direction) & bits.empty()) {
+                let Some(start) = board.neighbor(end, back) else {
+                    continue;
+                };
+                let is_king = kings & B::bit(start) != B::ZERO;
+                let ends_as_king = is_king || board.to_coord(end).0 == end_row;
+                moves.push(Move::new_move(start, end, is_king, ends_as_king));
+            }
+        }
+        if flying {
+            for position in positions(kings) {
+                moves.extend(self.plain_moves_for_pos(position));
+            }
+        }
+        // stable, so each piece keeps its moves in the order of its directions
+        moves.sort_by_key(Move::start);
+        moves
+    }
+
+    /// The current player's pieces that may start a capture: those next to a piece they can
+    /// capture with an empty square beyond it, and every flying king.
+    fn capture_candidates<B: Bits>(&self, bits: Bitboard<B>) -> B {
+        let own = bits.pieces(self.current_player);
+        let kings = own & bits.kings();
+        let opponent = bits.pieces(self.current_player.other());
+        let capturable_by_men = if self.rules.men_capture_kings {
+            opponent
         } else {
-            all_moves
+            opponent & !bits.kings()
+        };
+        let flying = self.rules.king_range == KingRange::Flying;
+        let man = match self.current_player {
+            Player::Red => Piece::red(),
+            Player::White => Piece::white(),
+        };
+
+        let mut candidates = if flying { kings } else { B::ZERO };
+        for direction in self.board.movement().directions() {
+            let back = (direction.0.wrapping_neg(), direction.1.wrapping_neg());
+            // pieces that would land on an empty square jumping in this direction
+            let jumped = bits.shift(bits.empty(), back);
+            if self.rules.men_capture_backwards
+                || man.directions(self.board.movement()).contains(direction)
+            {
+                candidates =
+                    candidates | bits.shift(jumped & capturable_by_men, back) & own & !kings;
+            }
+            if !flying {
+                candidates = candidates | bits.shift(jumped & opponent, back) & kings;
+            }
         }
+        candidates
     }
 
     pub fn get_player_piece_count(&self, player: Player) -> usize {
@@ -176,77 +795,283 @@ impl Checkers {
             .fold(0, |x, _| x + 1)
     }
 
-    fn all_moves_for_player(&self, player: Player) -> Vec<Move> {
-        let piece_positions = self.board.get_player_piece_positions(player);
-        piece_positions
+    /// Every capture sequence the current player may start this turn.
+    fn capture_sequences_for_player(&self) -> Vec<Vec<Move>> {
+        let mut sequences = Vec::new();
+        let add = |pos: usize| {
+            let piece = self.board.get(pos).expect("piece guaranteed to be present");
+            sequences.extend(self.capture_sequences(pos, piece, pos, &mut Vec::new()));
+        };
+        // on bitboards only the pieces that may capture are tried
+        match self.board.bitboard() {
+            Some(AnyBitboard::U32(bits)) => positions(self.capture_candidates(bits)).for_each(add),
+            Some(AnyBitboard::U64(bits)) => positions(self.capture_candidates(bits)).for_each(add),
+            Some(AnyBitboard::U128(bits)) => positions(self.capture_candidates(bits)).for_each(add),
+            None => self
+                .board
+                .get_player_piece_positions(self.current_player)
+                .for_each(add),
+        }
+        self.apply_capture_precedence(sequences)
+    }
+
+    /// The rest of the capture sequences the selected piece is part way through.
+    fn capture_continuations(&self) -> Vec<Vec<Move>> {
+        let Some(position) = self.selected_piece else {
+            return Vec::new();
+        };
+        let turn = self.history.get_last_turn().expect("a capture was made");
+        let prefix: Vec<Move> = turn
+            .iter()
+            .filter(|m| !m.is_select() && !m.is_huff())
+            .cloned()
+            .collect();
+        let last_move = prefix.last().expect("a capture was made");
+        if !last_move.started_king()
+            && last_move.ended_king()
+            && self.rules.promotion == Promotion::EndsTurn
+        {
+            return Vec::new();
+        }
+
+        let piece = self.board.get(position).expect("piece must exist");
+        let mut captured = prefix
+            .iter()
+            .filter_map(|m| m.capture())
+            .map(|c| c.0)
+            .collect();
+        let continuations = self.capture_sequences(position, piece, position, &mut captured);
+        let full_sequences = continuations
             .into_iter()
-            .flat_map(|pos| self.moves_for_pos(pos))
+            .map(|c| prefix.iter().cloned().chain(c).collect())
+            .collect();
+        self.apply_capture_precedence(full_sequences)
+            .into_iter()
+            .map(|s| s[prefix.len()..].to_vec())
             .collect()
     }
 
-    fn moves_for_pos(&self, position: usize) -> Vec<Move> {
-        let coord = self.board.to_coord(position);
+    fn apply_capture_precedence(&self, sequences: Vec<Vec<Move>>) -> Vec<Vec<Move>> {
+        match self.rules.capture_precedence {
+            CapturePrecedence::Free => sequences,
+            CapturePrecedence::MostPieces => {
+                let most = sequences.iter().map(|s| s.len()).max().unwrap_or(0);
+                sequences.into_iter().filter(|s| s.len() == most).collect()
+            }
+            CapturePrecedence::Italian => {
+                // each criterion only decides between sequences tied on the ones before it
+                let priority = |s: &Vec<Move>| {
+                    let kings_captured: Vec<usize> = s
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, m)| m.capture().is_some_and(|c| c.1.is_king()))
+                        .map(|(i, _)| i)
+                        .collect();
+                    (
+                        s.len(),
+                        s.first().is_some_and(|m| m.started_king()),
+                        kings_captured.len(),
+                        std::cmp::Reverse(kings_captured),
+                    )
+                };
+                let best = sequences.iter().map(priority).max();
+                sequences
+                    .into_iter()
+                    .filter(|s| Some(priority(s)) == best)
+                    .collect()
+            }
+        }
+    }
+
+    /// Every capture sequence for `piece` standing on `position`. `vacated` is where the piece
+    /// started its turn, which counts as empty, and `captured` holds the positions of pieces
+    /// already captured this turn in the order they were captured, which can be neither jumped
+    /// again nor, unless the rules remove them at once, passed through.
+    fn capture_sequences(
+        &self,
+        position: usize,
+        piece: Piece,
+        vacated: usize,
+        captured: &mut Vec<usize>,
+    ) -> Vec<Vec<Move>> {
+        let mut sequences = Vec::new();
+        for step in self.capture_steps(position, piece, vacated, captured) {
+            let (cap_pos, cap_piece) = step.capture().expect("capture steps capture");
+            let crowned = !piece.is_king()
+                && (self.board.to_coord(step.end()).0 == self.end_row_for_piece(&piece)
+                    || (cap_piece.is_king() && self.rules.king_capture_crowns));
+            if crowned && self.rules.promotion == Promotion::EndsTurn {
+                sequences.push(vec![Move::new_capture(
+                    position,
+                    step.end(),
+                    false,
+                    true,
+                    cap_pos,
+                    cap_piece,
+                )]);
+                continue;
+            }
+
+            let mut next_piece = piece;
+            if crowned && self.rules.promotion == Promotion::ContinuesAsKing {
+                next_piece.to_king();
+            }
+            captured.push(cap_pos);
+            let continuations = self.capture_sequences(step.end(), next_piece, vacated, captured);
+            captured.pop();
+
+            if continuations.is_empty() {
+                sequences.push(vec![Move::new_capture(
+                    position,
+                    step.end(),
+                    piece.is_king(),
+                    piece.is_king() || crowned,
+                    cap_pos,
+                    cap_piece,
+                )]);
+            } else {
+                let step = Move::new_capture(
+                    position,
+                    step.end(),
+                    piece.is_king(),
+                    next_piece.is_king(),
+                    cap_pos,
+                    cap_piece,
+                );
+                sequences.extend(
+                    continuations
+                        .into_iter()
+                        .map(|c| std::iter::once(step.clone()).chain(c).collect()),
+                );
+            }
+        }
+        sequences
+    }
+
+    /// Single captures for `piece` standing on `position`, see [`Checkers::capture_sequences`].
+    fn capture_steps(
+        &self,
+        position: usize,
+        piece: Piece,
+        vacated: usize,
+        captured: &[usize],
+    ) -> Vec<Move> {
+        let removed = self.rules.capture_removal == CaptureRemoval::Immediate;
+        let is_empty = |pos: usize| {
+            pos == vacated
+                || if captured.contains(&pos) {
+                    removed
+                } else {
+                    self.board.get(pos).is_none()
+                }
+        };
+        // the way back to the last captured piece, which is not allowed once it is removed
+        let back = captured.last().filter(|_| removed).map(|&last| {
+            let (row, col) = self.board.to_coord(position);
+            let (last_row, last_col) = self.board.to_coord(last);
+            (
+                (last_row as isize - row as isize).signum() as usize,
+                (last_col as isize - col as isize).signum() as usize,
+            )
+        });
+        let flying = piece.is_king() && self.rules.king_range == KingRange::Flying;
+        let movement = self.board.movement();
+        let directions = if piece.is_king() || self.rules.men_capture_backwards {
+            movement.directions()
+        } else {
+            piece.directions(movement)
+        };
+
+        let mut moves = Vec::new();
+        for direction in directions.iter().filter(|d| Some(**d) != back) {
+            let mut cap_pos = self.board.neighbor(position, *direction);
+            while flying && cap_pos.is_some_and(is_empty) {
+                cap_pos = cap_pos.and_then(|p| self.board.neighbor(p, *direction));
+            }
+            let Some(cap_pos) = cap_pos.filter(|p| !captured.contains(p)) else {
+                continue;
+            };
+            let Some(cap_piece) = self.board.get(cap_pos).filter(|p| {
+                p.player() != piece.player()
+                    && (piece.is_king() || !p.is_king() || self.rules.men_capture_kings)
+            }) else {
+                continue;
+            };
+
+            let mut end_pos = if flying {
+                self.board.neighbor(cap_pos, *direction)
+            } else {
+                self.board.jump(position, *direction)
+            };
+            while let Some(end) = end_pos.filter(|p| is_empty(*p)) {
+                let ends_as_king =
+                    piece.is_king() || self.board.to_coord(end).0 == self.end_row_for_piece(&piece);
+                moves.push(Move::new_capture(
+                    position,
+                    end,
+                    piece.is_king(),
+                    ends_as_king,
+                    cap_pos,
+                    cap_piece,
+                ));
+                if !flying {
+                    break;
+                }
+                end_pos = self.board.neighbor(end, *direction);
+            }
+        }
+        moves
+    }
+
+    fn plain_moves_for_pos(&self, position: usize) -> Vec<Move> {
         let piece = self
             .board
             .get(position)
             .expect("piece guaranteed to be present");
-        piece
-            .directions()
-            .iter()
-            .filter_map(|d| {
-                let (i, j) = *d;
-                let cap_coord = (coord.0.wrapping_add(i), coord.1.wrapping_add(j));
-                if self.board.is_within_bounds(cap_coord) {
-                    let cap_pos = self.board.to_position(cap_coord);
-                    if let Some(cap_piece) = self.board.get(cap_pos) {
-                        if piece.player() != cap_piece.player() {
-                            let end_coord =
-                                (cap_coord.0.wrapping_add(i), cap_coord.1.wrapping_add(j));
-                            if self.board.is_within_bounds(end_coord) {
-                                let end_pos = self.board.to_position(end_coord);
-                                if self.board.get(end_pos).is_none() {
-                                    let starts_as_king = piece.is_king();
-                                    let ends_as_king = starts_as_king
-                                        || end_coord.0 == Self::end_row_for_piece(&piece)
-                                        || cap_piece.is_king();
-                                    return Some(Move::new_capture(
-                                        position,
-                                        end_pos,
-                                        starts_as_king,
-                                        ends_as_king,
-                                        cap_pos,
-                                        cap_piece,
-                                    ));
-                                }
-                            }
-                        }
-                        return None;
-                    } else {
-                        let starts_as_king = piece.is_king();
-                        let ends_as_king =
-                            starts_as_king || cap_coord.0 == Self::end_row_for_piece(&piece);
-                        return Some(Move::new_move(
-                            position,
-                            cap_pos,
-                            starts_as_king,
-                            ends_as_king,
-                        ));
-                    }
+        let flying = piece.is_king() && self.rules.king_range == KingRange::Flying;
+        let mut moves = Vec::new();
+        for direction in piece.directions(self.board.movement()) {
+            let mut end_pos = self.board.neighbor(position, *direction);
+            while let Some(end) = end_pos.filter(|p| self.board.get(*p).is_none()) {
+                let ends_as_king =
+                    piece.is_king() || self.board.to_coord(end).0 == self.end_row_for_piece(&piece);
+                moves.push(Move::new_move(position, end, piece.is_king(), ends_as_king));
+                if !flying {
+                    break;
                 }
-                None
-            })
-            .collect()
+                end_pos = self.board.neighbor(end, *direction);
+            }
+        }
+        moves
     }
 
-    pub fn make_a_move_from_api(&mut self, pos: usize) {
-        let (row, col) = self.board.to_coord(pos);
+    /// Single moves and captures for the piece on `position`, ignoring which of them the
+    /// rules allow this turn.
+    #[cfg(test)]
+    fn moves_for_pos(&self, position: usize) -> Vec<Move> {
+        let piece = self
+            .board
+            .get(position)
+            .expect("piece guaranteed to be present");
+        let mut moves = self.capture_steps(position, piece, position, &[]);
+        moves.extend(self.plain_moves_for_pos(position));
+        moves
+    }
+
+    /// Selects the piece on `pos`, or moves the selected piece there, ending the turn once
+    /// the piece cannot go any further.
+    pub fn make_a_move_from_api(&mut self, pos: usize) -> Result<(), CheckersError> {
+        if pos >= self.board.position_count() {
+            return Err(IllegalMove::OutOfBounds(pos).into());
+        }
         if let Some(position) = self.selected_piece {
-            self.move_piece(position, row, col);
+            self.move_piece(position, pos)?;
             if self.selectable_positions.is_empty() {
                 self.end_turn();
             }
+            Ok(())
         } else {
-            self.select_piece(row, col);
+            self.select_piece(pos)
         }
     }
 
@@ -267,17 +1092,31 @@ impl Checkers {
                 let row: Result<usize, _> = try_read!();
                 let col: Result<usize, _> = try_read!();
                 if let (Ok(row), Ok(col)) = (row, col) {
-                    if self.move_piece(position, row, col) {
+                    if self.board.is_within_bounds((row, col))
+                        && self
+                            .move_piece(position, self.board.to_position((row, col)))
+                            .is_ok()
+                    {
                         break;
                     }
+                    println!(
+                        "ERROR: Invalid position {:?}, please try again.",
+                        (row, col)
+                    );
                 } else {
                     println!("ERROR: I didn't catch that, please input your zero-indexed coordinates in format \"ROW <space> COLUMN\".");
                 }
             }
         } else {
+            if let Some(player) = self.draw_offer() {
+                println!(
+                    "\n{} offers a draw, type accept to agree or make a move to decline.",
+                    player
+                );
+            }
             loop {
                 println!(
-                    "\nSelect a piece (row <space> column):\n\
+                    "\nSelect a piece (row <space> column), or type undo, redo, resign, offer, accept or draw:\n\
                     Movable pieces: {:?}",
                     self.selectable_positions
                         .iter()
@@ -289,169 +1128,461 @@ impl Checkers {
                     let row: Result<usize, _> = try_read!("{}", input[0].bytes());
                     let col: Result<usize, _> = try_read!("{}", input[1].bytes());
                     if let (Ok(row), Ok(col)) = (row, col) {
-                        if self.select_piece(row, col) {
+                        if self.board.is_within_bounds((row, col))
+                            && self
+                                .select_piece(self.board.to_position((row, col)))
+                                .is_ok()
+                        {
                             break;
                         }
-                    } else if input[0] == "undo" {
-                        self.undo_last_turn();
+                        println!(
+                            "ERROR: Invalid position {:?}, please try again.",
+                            (row, col)
+                        );
                         continue;
                     }
-                    println!("ERROR: I didn't catch that, please input your zero-indexed coordinates in format \"ROW <space> COLUMN\".");
-                } else if !input.is_empty() && input[0] == "undo" {
-                    self.undo_last_turn();
-                    break;
+                }
+                match input.first().and_then(|command| self.terminal_command(command)) {
+                    Some(true) => break,
+                    Some(false) => {}
+                    None => println!("ERROR: I didn't catch that, please input your zero-indexed coordinates in format \"ROW <space> COLUMN\"."),
                 }
             }
         }
     }
 
-    fn move_piece(&mut self, position: usize, row: usize, col: usize) -> bool {
-        let started_as_king = self
-            .board
-            .get(position)
-            .expect("piece must exist")
-            .is_king();
-        let start_coord = self.board.to_coord(position);
-        let end_coord = (row, col);
-        let end_pos = self.board.to_position(end_coord);
+    /// Runs a word typed instead of a position, returning whether the player is done choosing
+    /// or `None` if it is not a command.
+    #[cfg(feature = "standalone")]
+    fn terminal_command(&mut self, command: &str) -> Option<bool> {
+        let done = match command {
+            "undo" if self.bot_player.is_some() || self.take_back_limit.is_some() => {
+                match self.take_back() {
+                    Ok(()) => {
+                        println!("\nLAST TURNS TAKEN BACK");
+                        self.show_board();
+                        false
+                    }
+                    Err(e) => {
+                        println!("ERROR: {}.", e);
+                        false
+                    }
+                }
+            }
+            "undo" => match self.undo_last_turn() {
+                Ok(()) => {
+                    println!("\nLAST TURN UNDONE");
+                    true
+                }
+                Err(e) => {
+                    println!("ERROR: {}.", e);
+                    false
+                }
+            },
+            "redo" => match self.redo() {
+                Ok(()) => {
+                    println!("\nTURN REDONE");
+                    true
+                }
+                Err(e) => {
+                    println!("ERROR: {}.", e);
+                    false
+                }
+            },
+            "resign" => {
+                self.resign();
+                true
+            }
+            "offer" => {
+                if self.offer_draw() {
+                    println!("Draw offered.");
+                } else {
+                    println!("ERROR: A draw cannot be offered now.");
+                }
+                false
+            }
+            "accept" => {
+                let accepted = self.accept_draw();
+                if !accepted {
+                    println!("ERROR: There is no draw offer to accept.");
+                }
+                accepted
+            }
+            "draw" => {
+                let claimed = self.claim_draw();
+                if !claimed {
+                    println!("ERROR: A draw cannot be claimed yet.");
+                }
+                claimed
+            }
+            _ => return None,
+        };
+        Some(done)
+    }
+
+    fn move_piece(&mut self, position: usize, end_pos: usize) -> Result<(), CheckersError> {
         if let Some(m) = self
             .valid_moves
             .iter()
             .find(|m| m.start() == position && m.end() == end_pos)
+            .cloned()
         {
             self.board.r#move(position, end_pos);
-            if let Some((cap_pos, cap_piece)) = m.capture() {
+            if let Some((cap_pos, _)) = m.capture() {
                 self.board.take(cap_pos);
-                if cap_piece.is_king() {
-                    self.board.get_mut(end_pos).map(|p| p.to_king());
-                    println!("\nCAPTURED KING PIECE {:?}", self.board.to_coord(cap_pos));
-                } else {
-                    println!("\nCAPTURED PIECE {:?}", self.board.to_coord(cap_pos));
-                }
-
-                self.king_if_end_row(end_pos);
-                self.history.push(
-                    self.current_player,
-                    Move::new_capture(
-                        position,
-                        end_pos,
-                        started_as_king,
-                        self.board.get(end_pos).expect("just moved here").is_king(),
-                        cap_pos,
-                        cap_piece,
-                    ),
-                );
-            } else {
-                self.king_if_end_row(end_pos);
-                self.history.push(
-                    self.current_player,
-                    Move::new_move(
-                        position,
-                        end_pos,
-                        started_as_king,
-                        self.board.get(end_pos).expect("just moved here").is_king(),
-                    ),
-                );
             }
-            println!(
-                "PIECE MOVED {:?} -> {:?}\n",
-                start_coord,
-                self.board.to_coord(end_pos)
-            );
+            if m.ended_king() {
+                self.board.crown(end_pos);
+            }
+            self.history.push(self.current_player, m.clone());
             self.selected_piece = Some(end_pos); // NOTE selected piece update enables chaining captures
             self.update_valid_moves();
-            true
+            self.emit(GameEvent::PieceMoved {
+                from: position,
+                to: end_pos,
+            });
+            if let Some((cap_pos, cap_piece)) = m.capture() {
+                self.emit(GameEvent::PieceCaptured {
+                    position: cap_pos,
+                    piece: cap_piece,
+                });
+            }
+            if m.ended_king() && !m.started_king() {
+                self.emit(GameEvent::PieceCrowned { position: end_pos });
+            }
+            Ok(())
         } else {
-            println!("ERROR: Invalid position {:?}, please try again.", end_coord);
-            false
+            Err(IllegalMove::NotADestination {
+                from: position,
+                to: end_pos,
+            }
+            .into())
         }
     }
 
-    fn select_piece(&mut self, row: usize, col: usize) -> bool {
-        let coord = (row, col);
-        let pos = self.board.to_position(coord);
+    fn select_piece(&mut self, pos: usize) -> Result<(), CheckersError> {
+        let piece = self.board.get(pos);
+        if let Some(huffed) = piece.filter(|p| p.player() != self.current_player) {
+            if !self.selectable_positions.contains(&pos) {
+                return Err(CheckersError::WrongPlayer {
+                    position: pos,
+                    owner: huffed.player(),
+                });
+            }
+            self.decline_draw();
+            self.board.take(pos);
+            self.history
+                .push(self.current_player, Move::new_huff(pos, huffed));
+            self.update_valid_moves();
+            self.emit(GameEvent::PieceHuffed {
+                position: pos,
+                piece: huffed,
+            });
+            return Ok(());
+        }
         if self.selectable_positions.contains(&pos) {
+            self.decline_draw();
             self.selected_piece = Some(pos);
-            println!("\nPIECE SELECTED {:?}\n", coord);
             self.update_selectable_positions();
-            let piece = self.board.get(pos).expect("piece must exist");
-            let is_king = piece.is_king();
+            let is_king = piece.is_some_and(|p| p.is_king());
             self.history
                 .push(self.current_player, Move::new_select(pos, is_king));
-            true
+            self.emit(GameEvent::PieceSelected { position: pos });
+            Ok(())
         } else {
-            println!("ERROR: Invalid position {:?}, please try again.", coord);
-            false
+            Err(IllegalMove::NotMovable(pos).into())
         }
     }
 
-    fn king_if_end_row(&mut self, position: usize) {
-        let (pos_row, _) = self.board.to_coord(position);
-        if let Some(piece) = self.board.get_mut(position) {
-            if !piece.is_king() {
-                let end_row = Self::end_row_for_piece(piece);
-                if pos_row == end_row {
-                    piece.to_king();
-                }
+    fn end_row_for_piece(&self, piece: &Piece) -> usize {
+        match piece.player() {
+            Player::Red => self.board.rows() - 1,
+            Player::White => 0,
+        }
+    }
+
+    /// Takes back the last turn, see [`Checkers::undo`].
+    pub fn undo_last_turn(&mut self) -> Result<(), CheckersError> {
+        self.undo()
+    }
+
+    /// Takes back the last step, which may be the end of the previous turn unless take-backs
+    /// are limited.
+    pub fn undo_last_move(&mut self) -> Result<(), CheckersError> {
+        self.check_unlimited_undo()?;
+        self.undo_step()
+    }
+
+    /// With a take-back limit, finished turns may only be taken back with
+    /// [`Checkers::take_back`], which counts them.
+    fn check_unlimited_undo(&self) -> Result<(), CheckersError> {
+        if self.take_back_limit.is_some()
+            && self
+                .history
+                .get_last_player()
+                .is_some_and(|p| p != self.current_player)
+        {
+            return Err(CheckersError::LimitedTakeBacks);
+        }
+        Ok(())
+    }
+
+    fn undo_step(&mut self) -> Result<(), CheckersError> {
+        if self.history.get_last_move().is_none() {
+            return Err(CheckersError::NothingToUndo);
+        }
+        self.result = None;
+        self.history
+            .truncate_actions(self.turns_played().saturating_sub(1));
+        let previous_turn = self
+            .history
+            .get_last_player()
+            .is_some_and(|p| p != self.current_player);
+        if previous_turn {
+            // the last move ended the previous turn, step back into it
+            self.positions.pop();
+            self.current_player = self.current_player.other();
+        }
+        let started_last_move_as_king = self.history.started_last_move_as_king();
+        let Some(m) = self.history.pop_last_move() else {
+            return Err(CheckersError::NothingToUndo);
+        };
+        if m.is_huff() {
+            if let Some((pos, piece)) = m.capture() {
+                self.board.set(pos, Some(piece));
             }
+            self.selected_piece = None;
+            self.update_valid_moves();
+            self.emit_undone(previous_turn);
+            return Ok(());
         }
+        if let Some(mut piece) = self.board.take(m.end()) {
+            if !started_last_move_as_king {
+                piece.remove_king();
+            }
+            self.board.set(m.start(), Some(piece));
+        }
+        if let Some((cap_pos, cap_piece)) = m.capture() {
+            self.board.set(cap_pos, Some(cap_piece));
+        }
+        // undoing the selection puts the player back at the start of their turn
+        self.selected_piece =
+            if !m.is_select() && self.history.get_last_player() == Some(self.current_player) {
+                Some(m.start())
+            } else {
+                None
+            };
+        self.update_valid_moves();
+        self.emit_undone(previous_turn);
+        Ok(())
     }
 
-    fn end_row_for_piece(piece: &Piece) -> usize {
-        match piece.player() {
-            Player::Red => 7,
-            Player::White => 0,
+    fn emit_undone(&mut self, previous_turn: bool) {
+        if self.is_observed() {
+            self.emit(GameEvent::StepUndone);
+            if previous_turn {
+                self.emit(GameEvent::TurnChanged {
+                    player: self.current_player,
+                });
+            }
         }
     }
 
-    pub fn undo_last_turn(&mut self) {
-        let last_turn_moves = self.history.pop_last_turn();
-        if let Some(mut moves) = last_turn_moves {
-            let started_last_turn_as_king = self.history.started_last_turn_as_king();
-            while let Some(m) = moves.pop() {
-                let mut piece = self.board.take(m.end()).expect("ended turn here");
-                if !started_last_turn_as_king {
-                    piece.remove_king();
-                }
-                if let Some((cap_pos, cap_piece)) = m.capture() {
-                    self.board.set(cap_pos, Some(cap_piece));
+    /// Every way the player to move can play out the rest of their turn, none once the game
+    /// is over.
+    pub fn legal_turns(&self) -> Vec<Turn> {
+        fn collect(game: &mut Checkers, steps: &mut Vec<usize>, turns: &mut Vec<Turn>) {
+            let player = game.current_player;
+            for pos in game.selectable_positions.clone() {
+                game.make_a_move_from_api(pos)
+                    .expect("selectable positions can be played");
+                steps.push(pos);
+                if game.current_player == player {
+                    collect(game, steps, turns);
+                } else {
+                    turns.push(Turn::new(steps.clone()));
                 }
-                self.board.set(m.start(), Some(piece));
+                steps.pop();
+                game.undo_step().expect("a step was just played");
             }
-            self.selectable_positions = Vec::new();
-            #[cfg(feature = "standalone")]
-            println!("\nLAST TURN UNDONE");
         }
+
+        let mut turns = Vec::new();
+        if self.outcome().is_some() {
+            return turns;
+        }
+        let mut game = self.clone();
+        collect(&mut game, &mut Vec::new(), &mut turns);
+        turns
     }
 
-    pub fn undo_last_move(&mut self) {
-        if self.selected_piece.is_none() {
-            self.selected_piece = Some(self.history.get_last_move().unwrap().end());
+    /// Plays the rest of the current player's turn. If any step is not allowed, or the steps
+    /// do not finish the turn, the game is left as it was and an error is returned.
+    pub fn apply_turn(&mut self, turn: &Turn) -> Result<(), CheckersError> {
+        if let Some(outcome) = self.outcome() {
+            return Err(IllegalMove::GameOver(outcome).into());
         }
-        let started_last_move_as_king = self.history.started_last_move_as_king();
-        let last_move = self.history.pop_last_move();
-        if let Some(m) = last_move {
-            let mut piece = self.board.take(m.end()).expect("ended turn here");
-            if !started_last_move_as_king {
-                piece.remove_king();
+        if self.is_observed() {
+            // try the turn on a copy first, so observers only hear about turns that stand
+            self.clone().apply_turn(turn)?;
+        }
+        let player = self.current_player;
+        // playing a step forgets the steps taken back, which a rejected turn must not do
+        let undone = self.history.undone.clone();
+        let mut applied = 0;
+        let result = loop {
+            let Some(&pos) = turn.steps().get(applied) else {
+                break Err(IllegalMove::TurnUnfinished.into());
+            };
+            if self.current_player != player {
+                break Err(IllegalMove::TurnFinished.into());
             }
-            if let Some((cap_pos, cap_piece)) = m.capture() {
-                self.board.set(cap_pos, Some(cap_piece));
+            if let Err(e) = self.make_a_move_from_api(pos) {
+                break Err(e);
+            }
+            applied += 1;
+            if self.current_player != player && applied == turn.steps().len() {
+                break Ok(());
+            }
+        };
+        if result.is_err() {
+            for _ in 0..applied {
+                self.undo_step().expect("the step was just played");
+            }
+            self.history.undone = undone;
+        }
+        result
+    }
+
+    /// The player whose turn it is.
+    pub fn side_to_move(&self) -> Player {
+        self.current_player
+    }
+
+    /// Takes back the last turn, or the steps played so far of the current one, leaving the
+    /// game at the start of that turn. With a take-back limit only the steps of the current
+    /// turn can be taken back.
+    pub fn undo(&mut self) -> Result<(), CheckersError> {
+        let player = self
+            .history
+            .get_last_player()
+            .ok_or(CheckersError::NothingToUndo)?;
+        loop {
+            self.undo_last_move()?;
+            if self.current_player == player && self.at_turn_start() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Plays again the turn taken back by the last [`Checkers::undo`], or as much of it as
+    /// was taken back.
+    pub fn redo(&mut self) -> Result<(), CheckersError> {
+        let player = self.current_player;
+        self.redo_last_move()?;
+        while self.current_player == player && self.history.next_undone().is_some() {
+            self.redo_last_move()?;
+        }
+        Ok(())
+    }
+
+    /// Plays again the last step taken back, as long as no other step was played since.
+    pub fn redo_last_move(&mut self) -> Result<(), CheckersError> {
+        let m = self
+            .history
+            .next_undone()
+            .ok_or(CheckersError::NothingToRedo)?;
+        // the step's position is where it ended, which is also the position of a selected or
+        // huffed piece
+        self.make_a_move_from_api(m.end())
+    }
+
+    /// Takes back `steps` steps, or none if fewer have been played or they would take back a
+    /// finished turn while take-backs are limited.
+    pub fn step_back(&mut self, steps: usize) -> Result<(), CheckersError> {
+        if steps > self.history.step_count() {
+            return Err(CheckersError::NothingToUndo);
+        }
+        for taken in 0..steps {
+            if let Err(e) = self.undo_last_move() {
+                self.step_forward(taken)?;
+                return Err(e);
             }
-            self.board.set(m.start(), Some(piece));
-            self.selected_piece = Some(m.start());
-            self.update_valid_moves();
-            #[cfg(feature = "standalone")]
-            println!("\nLAST TURN UNDONE");
-        } else {
-            panic!("no last move to undo")
         }
+        Ok(())
+    }
+
+    /// Plays again `steps` of the steps taken back, or none if fewer were taken back.
+    pub fn step_forward(&mut self, steps: usize) -> Result<(), CheckersError> {
+        if steps > self.history.undone.len() {
+            return Err(CheckersError::NothingToRedo);
+        }
+        for _ in 0..steps {
+            self.redo_last_move()?;
+        }
+        Ok(())
+    }
+
+    /// Steps back or forward to the start of turn `turn`, counting the first turn of the game
+    /// as 0. Going forward only replays steps taken back, and if there are not enough of them
+    /// the game is left as it was. With a take-back limit it cannot go back to a finished turn.
+    pub fn go_to_turn(&mut self, turn: usize) -> Result<(), CheckersError> {
+        if self.take_back_limit.is_some() && turn < self.turns_played() {
+            return Err(CheckersError::LimitedTakeBacks);
+        }
+        self.seek_turn(turn)
+    }
+
+    fn seek_turn(&mut self, turn: usize) -> Result<(), CheckersError> {
+        let steps = self.history.step_count();
+        while self.turns_played() > turn || !self.at_turn_start() {
+            self.undo_step()?;
+        }
+        while self.turns_played() < turn {
+            if let Err(e) = self.redo_last_move() {
+                while self.history.step_count() > steps {
+                    self.undo_step()?;
+                }
+                self.step_forward(steps - self.history.step_count())?;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes back the last turn of the player to move together with the opponent's reply, so
+    /// the same player is to move again, and records the take-back in the history. Steps
+    /// played so far this turn are taken back too. With a take-back limit this is the only way
+    /// to take back a finished turn.
+    pub fn take_back(&mut self) -> Result<(), CheckersError> {
+        let player = self.current_player;
+        if let Some(limit) = self.take_back_limit {
+            if self.history.take_backs(player) >= limit {
+                return Err(CheckersError::TakeBackLimit(limit));
+            }
+        }
+        let turn = self.positions[..self.turns_played()]
+            .iter()
+            .rposition(|(_, p)| *p == player)
+            .ok_or(CheckersError::NothingToUndo)?;
+        self.seek_turn(turn)?;
+        self.record_action(GameAction::TakeBack(player));
+        Ok(())
+    }
+
+    /// Number of turns finished so far.
+    pub fn turns_played(&self) -> usize {
+        self.positions.len().saturating_sub(1)
     }
 
     pub fn selectable_positions(&self) -> &[usize] {
         self.selectable_positions.as_ref()
     }
 
+    pub fn selected_piece(&self) -> Option<usize> {
+        self.selected_piece
+    }
+
     pub fn current_player(&self) -> Player {
         self.current_player
     }
@@ -459,11 +1590,20 @@ impl Checkers {
     pub fn board(&self) -> &Board {
         &self.board
     }
+
+    pub fn rules(&self) -> &Ruleset {
+        &self.rules
+    }
+
+    pub(crate) fn board_mut(&mut self) -> &mut Board {
+        &mut self.board
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::learning::XorShift;
     use std::collections::HashSet;
 
     fn eq_ignore_order(a: &[Move], b: &[Move]) -> bool {
@@ -485,4 +1625,1143 @@ mod tests {
             ][..]
         ));
     }
+
+    #[test]
+    fn test_undo_last_move_across_turns() {
+        let mut checkers = Checkers::default();
+        checkers.update_valid_moves();
+        let start = checkers.clone();
+
+        checkers.make_a_move_from_api(8).unwrap();
+        checkers.make_a_move_from_api(12).unwrap();
+        assert_eq!(checkers.current_player(), Player::White);
+        assert_eq!(checkers.position_repetitions(), 1);
+
+        checkers.undo_last_move().unwrap();
+        assert_eq!(checkers.current_player(), Player::Red);
+        assert_eq!(checkers.selected_piece(), Some(8));
+        assert_eq!(checkers.position_repetitions(), 0);
+
+        checkers.undo_last_move().unwrap();
+        assert_eq!(checkers.selected_piece(), None);
+        assert_eq!(checkers.board(), start.board());
+        assert_eq!(
+            checkers.selectable_positions(),
+            start.selectable_positions()
+        );
+    }
+
+    fn king(mut piece: Piece) -> Piece {
+        piece.to_king();
+        piece
+    }
+
+    fn game_with(rules: Ruleset, pieces: &[(usize, Piece)]) -> Checkers {
+        let mut layout = vec![None; Board::for_rules(&rules).unwrap().position_count()];
+        for (pos, piece) in pieces {
+            layout[*pos] = Some(*piece);
+        }
+        let mut game = Checkers {
+            board: Board::with_layout(rules.board_size, rules.movement, layout).unwrap(),
+            rules,
+            ..Default::default()
+        };
+        game.start_headless();
+        game
+    }
+
+    fn targets_after_selecting(game: &mut Checkers, pos: usize) -> HashSet<usize> {
+        game.make_a_move_from_api(pos).unwrap();
+        game.selectable_positions().iter().copied().collect()
+    }
+
+    #[test]
+    fn test_with_rules_sets_first_player() {
+        let rules = Ruleset {
+            first_player: Player::White,
+            ..Default::default()
+        };
+        assert_eq!(
+            Checkers::with_rules(rules, None).unwrap().current_player(),
+            Player::White
+        );
+    }
+
+    #[test]
+    fn test_king_range() {
+        let short = game_with(Ruleset::english(), &[(13, king(Piece::red()))]);
+        assert_eq!(short.plain_moves_for_pos(13).len(), 4);
+        let rules = Ruleset {
+            king_range: KingRange::Flying,
+            ..Default::default()
+        };
+        let flying = game_with(rules, &[(13, king(Piece::red()))]);
+        assert_eq!(flying.plain_moves_for_pos(13).len(), 11);
+    }
+
+    #[test]
+    fn test_capture_precedence() {
+        let pieces = [
+            (1, Piece::red()),
+            (5, Piece::white()),
+            (6, Piece::white()),
+            (14, Piece::white()),
+        ];
+        let mut free = game_with(Ruleset::english(), &pieces);
+        assert_eq!(
+            targets_after_selecting(&mut free, 1),
+            HashSet::from([8, 10])
+        );
+        let rules = Ruleset {
+            capture_precedence: CapturePrecedence::MostPieces,
+            ..Default::default()
+        };
+        let mut most = game_with(rules, &pieces);
+        assert_eq!(targets_after_selecting(&mut most, 1), HashSet::from([10]));
+    }
+
+    #[test]
+    fn test_mandatory_capture() {
+        let pieces = [(1, Piece::red()), (5, Piece::white())];
+        let mut mandatory = game_with(Ruleset::english(), &pieces);
+        assert_eq!(
+            targets_after_selecting(&mut mandatory, 1),
+            HashSet::from([8])
+        );
+        let rules = Ruleset {
+            mandatory_capture: false,
+            ..Default::default()
+        };
+        let mut optional = game_with(rules, &pieces);
+        assert_eq!(
+            targets_after_selecting(&mut optional, 1),
+            HashSet::from([6, 8])
+        );
+    }
+
+    #[test]
+    fn test_men_capture_backwards() {
+        let pieces = [(13, Piece::red()), (8, Piece::white())];
+        let mut forwards = game_with(Ruleset::english(), &pieces);
+        assert_eq!(
+            targets_after_selecting(&mut forwards, 13),
+            HashSet::from([16, 17])
+        );
+        let rules = Ruleset {
+            men_capture_backwards: true,
+            ..Default::default()
+        };
+        let mut backwards = game_with(rules, &pieces);
+        assert_eq!(
+            targets_after_selecting(&mut backwards, 13),
+            HashSet::from([4])
+        );
+    }
+
+    #[test]
+    fn test_promotion_ends_turn() {
+        // the red man is crowned on 29 and may not carry on to capture 25
+        let pieces = [
+            (20, Piece::red()),
+            (24, Piece::white()),
+            (25, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::english(), &pieces);
+        game.make_a_move_from_api(20).unwrap();
+        game.make_a_move_from_api(29).unwrap();
+        assert!(game.board().get(29).unwrap().is_king());
+        assert_eq!(game.current_player(), Player::White);
+    }
+
+    #[test]
+    fn test_international_start() {
+        let game = Checkers::with_rules(Ruleset::international(), None).unwrap();
+        assert_eq!(game.get_player_piece_count(Player::Red), 20);
+        assert_eq!(game.get_player_piece_count(Player::White), 20);
+        assert_eq!(game.current_player(), Player::White);
+    }
+
+    #[test]
+    fn test_international_no_promotion_passing_through() {
+        // the red man jumps 40 onto the far row and must carry on backwards over 41
+        let pieces = [
+            (35, Piece::red()),
+            (40, Piece::white()),
+            (41, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::international(), &pieces);
+        assert_eq!(targets_after_selecting(&mut game, 35), HashSet::from([46]));
+        game.make_a_move_from_api(46).unwrap();
+        assert!(!game.board().get(46).unwrap().is_king());
+        assert_eq!(game.selectable_positions(), &[37]);
+        game.make_a_move_from_api(37).unwrap();
+        assert!(!game.board().get(37).unwrap().is_king());
+        assert_eq!(game.current_player(), Player::White);
+    }
+
+    #[test]
+    fn test_international_flying_king_capture() {
+        // the king may land on any empty square beyond the captured man
+        let pieces = [(0, king(Piece::red())), (11, Piece::white())];
+        let mut game = game_with(Ruleset::international(), &pieces);
+        assert_eq!(
+            targets_after_selecting(&mut game, 0),
+            HashSet::from([17, 22, 28, 33, 39, 44])
+        );
+    }
+
+    #[test]
+    fn test_russian_promotion_continues_as_king() {
+        // crowned on 29, the new king flies on over 25 to any square beyond it
+        let pieces = [
+            (20, Piece::red()),
+            (24, Piece::white()),
+            (25, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::russian(), &pieces);
+        game.make_a_move_from_api(20).unwrap();
+        game.make_a_move_from_api(29).unwrap();
+        assert!(game.board().get(29).unwrap().is_king());
+        assert_eq!(
+            game.selectable_positions()
+                .iter()
+                .copied()
+                .collect::<HashSet<_>>(),
+            HashSet::from([22, 18, 15, 11])
+        );
+        game.make_a_move_from_api(15).unwrap();
+        assert_eq!(game.current_player(), Player::White);
+        assert_eq!(game.get_player_piece_count(Player::White), 0);
+    }
+
+    #[test]
+    fn test_russian_any_capture_sequence() {
+        // capturing one piece is allowed even though capturing two is possible
+        let pieces = [
+            (1, Piece::red()),
+            (5, Piece::white()),
+            (6, Piece::white()),
+            (14, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::russian(), &pieces);
+        assert_eq!(
+            targets_after_selecting(&mut game, 1),
+            HashSet::from([8, 10])
+        );
+    }
+
+    #[test]
+    fn test_italian_most_pieces() {
+        let pieces = [
+            (1, Piece::red()),
+            (5, Piece::white()),
+            (6, Piece::white()),
+            (14, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::italian(), &pieces);
+        assert_eq!(targets_after_selecting(&mut game, 1), HashSet::from([10]));
+    }
+
+    #[test]
+    fn test_italian_capture_with_king() {
+        // the king on 13 and the man on 10 can each capture one man, only the king may
+        let pieces = [
+            (13, king(Piece::red())),
+            (17, Piece::white()),
+            (10, Piece::red()),
+            (15, Piece::white()),
+        ];
+        let game = game_with(Ruleset::italian(), &pieces);
+        assert_eq!(game.selectable_positions(), &[13]);
+    }
+
+    #[test]
+    fn test_italian_most_kings() {
+        // capturing the king on 16 is preferred over the man on 17
+        let pieces = [
+            (13, king(Piece::red())),
+            (16, king(Piece::white())),
+            (17, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::italian(), &pieces);
+        assert_eq!(targets_after_selecting(&mut game, 13), HashSet::from([20]));
+    }
+
+    #[test]
+    fn test_italian_earliest_king() {
+        // both sequences capture a king and a man, the one taking the king first is required
+        let pieces = [
+            (13, king(Piece::red())),
+            (17, Piece::white()),
+            (26, king(Piece::white())),
+            (16, king(Piece::white())),
+            (24, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::italian(), &pieces);
+        assert_eq!(targets_after_selecting(&mut game, 13), HashSet::from([20]));
+        game.make_a_move_from_api(20).unwrap();
+        assert_eq!(game.selectable_positions(), &[29]);
+    }
+
+    #[test]
+    fn test_italian_men_cannot_capture_kings() {
+        let pieces = [(9, Piece::red()), (13, king(Piece::white()))];
+        let mut game = game_with(Ruleset::italian(), &pieces);
+        assert_eq!(targets_after_selecting(&mut game, 9), HashSet::from([14]));
+        let mut english = game_with(Ruleset::english(), &pieces);
+        assert_eq!(
+            targets_after_selecting(&mut english, 9),
+            HashSet::from([16])
+        );
+    }
+
+    #[test]
+    fn test_turkish_start() {
+        let mut game = Checkers::with_rules(Ruleset::turkish(), None).unwrap();
+        game.start_headless();
+        assert_eq!(game.current_player(), Player::White);
+        assert_eq!(game.selectable_positions(), (40..48).collect::<Vec<_>>());
+        assert_eq!(game.legal_turns().len(), 8);
+    }
+
+    #[test]
+    fn test_turkish_men_move_forwards_and_sideways() {
+        let mut game = game_with(Ruleset::turkish(), &[(27, Piece::red())]);
+        assert_eq!(
+            targets_after_selecting(&mut game, 27),
+            HashSet::from([26, 28, 35])
+        );
+
+        // the man on 19 is behind, so only the sideways capture is possible
+        let pieces = [
+            (27, Piece::red()),
+            (28, Piece::white()),
+            (19, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::turkish(), &pieces);
+        assert_eq!(targets_after_selecting(&mut game, 27), HashSet::from([29]));
+    }
+
+    #[test]
+    fn test_turkish_flying_king_takes_most_pieces() {
+        // along the top row then down the sixth column captures two, down the first column one
+        let pieces = [
+            (0, king(Piece::red())),
+            (3, Piece::white()),
+            (21, Piece::white()),
+            (16, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::turkish(), &pieces);
+        assert_eq!(targets_after_selecting(&mut game, 0), HashSet::from([5]));
+        game.make_a_move_from_api(5).unwrap();
+        assert_eq!(
+            game.selectable_positions()
+                .iter()
+                .copied()
+                .collect::<HashSet<_>>(),
+            HashSet::from([29, 37, 45, 53, 61])
+        );
+    }
+
+    #[test]
+    fn test_turkish_captured_pieces_are_removed_at_once() {
+        // right along the third row, down the fifth column, left along the sixth row and up
+        // the third column, landing where the first man captured stood
+        let pieces = [
+            (16, king(Piece::red())),
+            (18, Piece::white()),
+            (36, Piece::white()),
+            (43, Piece::white()),
+            (26, Piece::white()),
+        ];
+        let game = game_with(Ruleset::turkish(), &pieces);
+        let turns = game.legal_turns();
+        assert!(turns.contains(&Turn::new(vec![16, 20, 44, 42, 18])));
+        assert!(turns.iter().all(|t| t.steps().len() == 5));
+
+        // left on the board until the turn ends, the first man blocks the last capture
+        let rules = Ruleset {
+            capture_removal: CaptureRemoval::EndOfTurn,
+            ..Ruleset::turkish()
+        };
+        let game = game_with(rules, &pieces);
+        assert!(game.legal_turns().iter().all(|t| t.steps().len() == 4));
+    }
+
+    #[test]
+    fn test_turkish_capture_cannot_turn_back() {
+        // after capturing either man the king may not turn around to capture the other
+        let pieces = [
+            (26, king(Piece::red())),
+            (25, Piece::white()),
+            (28, Piece::white()),
+        ];
+        let game = game_with(Ruleset::turkish(), &pieces);
+        let mut turns = game.legal_turns();
+        turns.sort_by_key(|t| t.steps().to_vec());
+        assert_eq!(
+            turns,
+            [24, 29, 30, 31]
+                .map(|end| Turn::new(vec![26, end]))
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn test_giveaway_player_without_pieces_wins() {
+        let pieces = [(9, Piece::red()), (18, Piece::white())];
+        for (rules, winner, message) in [
+            (
+                Ruleset::english(),
+                Player::White,
+                "White wins, the other player cannot move",
+            ),
+            (
+                Ruleset::giveaway(),
+                Player::Red,
+                "Red wins by running out of moves",
+            ),
+        ] {
+            let mut game = game_with(rules, &pieces);
+            assert_eq!(game.winner(), None);
+            for pos in [9, 14, 18, 9] {
+                game.make_a_move_from_api(pos).unwrap();
+            }
+            assert_eq!(game.current_player(), Player::Red);
+            assert_eq!(game.winner(), Some(winner));
+            assert_eq!(game.outcome().unwrap().to_string(), message);
+        }
+    }
+
+    #[test]
+    fn test_huffing_piece_that_skipped_a_capture() {
+        // red can capture 14 from 10 but moves 8 instead, so white may huff the man on 10
+        let pieces = [
+            (8, Piece::red()),
+            (10, Piece::red()),
+            (14, Piece::white()),
+            (23, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::casual(), &pieces);
+        assert_eq!(
+            targets_after_selecting(&mut game, 10),
+            HashSet::from([17, 15])
+        );
+        game.undo_last_move().unwrap();
+        for pos in [8, 12] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert_eq!(game.current_player(), Player::White);
+        assert!(game.selectable_positions().contains(&10));
+        assert!(!game.selectable_positions().contains(&12));
+
+        game.make_a_move_from_api(10).unwrap();
+        assert_eq!(game.board().get(10), None);
+        assert_eq!(game.current_player(), Player::White);
+        assert!(!game.selectable_positions().contains(&12));
+        for pos in [23, 19] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert_eq!(game.current_player(), Player::Red);
+        // 14 could only have captured the man white huffed before moving
+        assert!(!game.selectable_positions().contains(&14));
+
+        // undo back to before the huff and play a turn without it
+        for _ in 0..3 {
+            game.undo_last_move().unwrap();
+        }
+        assert_eq!(game.board().get(10), Some(Piece::red()));
+        assert!(game.selectable_positions().contains(&10));
+        for pos in [14, 9] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert_eq!(game.current_player(), Player::Red);
+    }
+
+    #[test]
+    fn test_no_huffing_after_a_capture_or_without_the_rule() {
+        let pieces = [
+            (8, Piece::red()),
+            (10, Piece::red()),
+            (14, Piece::white()),
+            (23, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::casual(), &pieces);
+        for pos in [10, 17] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert!(game
+            .selectable_positions()
+            .iter()
+            .all(|p| game.board().get(*p).unwrap().player() == Player::White));
+
+        let mut rules = Ruleset::casual();
+        rules.huffing = false;
+        let mut game = game_with(rules, &pieces);
+        for pos in [8, 12] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert!(!game.selectable_positions().contains(&10));
+    }
+
+    #[test]
+    fn test_king_capture_crowns_house_rule() {
+        // the man on 1 captures the king on 5 and could go on to capture the man on 13
+        let pieces = [
+            (1, Piece::red()),
+            (5, king(Piece::white())),
+            (13, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::english(), &pieces);
+        for pos in [1, 8] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert_eq!(game.current_player(), Player::Red);
+        assert!(!game.board().get(8).unwrap().is_king());
+
+        let mut rules = Ruleset::english();
+        rules.king_capture_crowns = true;
+        let mut game = game_with(rules, &pieces);
+        for pos in [1, 8] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert_eq!(game.current_player(), Player::White);
+        assert!(game.board().get(8).unwrap().is_king());
+        game.undo_last_move().unwrap();
+        assert!(!game.board().get(1).unwrap().is_king());
+        assert_eq!(game.board().get(5), Some(king(Piece::white())));
+    }
+
+    #[test]
+    fn test_promotion_rows_for_every_size() {
+        for size in [6, 8, 10, 12] {
+            let rules = Ruleset {
+                board_size: size,
+                starting_rows: Board::default_starting_rows(size),
+                ..Default::default()
+            };
+            let half = size / 2;
+            // a red man one row from the far side and a white man one row from the near side
+            let red = (size - 2) * half;
+            let white = half;
+            let mut game = game_with(rules, &[(red, Piece::red()), (white, Piece::white())]);
+            let target = *targets_after_selecting(&mut game, red)
+                .iter()
+                .next()
+                .unwrap();
+            game.make_a_move_from_api(target).unwrap();
+            assert!(game.board().get(target).unwrap().is_king(), "size {}", size);
+
+            let target = *targets_after_selecting(&mut game, white)
+                .iter()
+                .next()
+                .unwrap();
+            game.make_a_move_from_api(target).unwrap();
+            assert_eq!(game.board().to_coord(target).0, 0);
+            assert!(game.board().get(target).unwrap().is_king(), "size {}", size);
+        }
+    }
+
+    #[test]
+    fn test_custom_board_sizes_can_be_played() {
+        for size in [6, 8, 10, 12, 18] {
+            let mut game = Checkers::custom_board(size, None).unwrap();
+            game.start_headless();
+            assert_eq!(game.selectable_positions().len(), size / 2, "size {}", size);
+        }
+    }
+
+    #[test]
+    fn test_rectangular_board_promotes_on_last_row() {
+        let rules = Ruleset {
+            board_rows: Some(10),
+            starting_rows: 4,
+            ..Default::default()
+        };
+        let mut game = Checkers::with_rules(rules, None).unwrap();
+        game.start_headless();
+        assert_eq!(game.board().rows(), 10);
+        assert_eq!(game.selectable_positions(), &[12, 13, 14, 15]);
+
+        let mut layout = vec![None; 40];
+        layout[33] = Some(Piece::red());
+        game.board = Board::with_layout(8, Movement::Diagonal, layout).unwrap();
+        game.update_valid_moves();
+        let target = *targets_after_selecting(&mut game, 33)
+            .iter()
+            .next()
+            .unwrap();
+        game.make_a_move_from_api(target).unwrap();
+        assert_eq!(game.board().to_coord(target).0, 9);
+        assert!(game.board().get(target).unwrap().is_king());
+    }
+
+    /// Red and white kings that can shuffle back and forth, with `rules` changed by `change`.
+    fn shuffling_kings(change: impl FnOnce(&mut Ruleset)) -> Checkers {
+        let mut rules = Ruleset::english();
+        change(&mut rules);
+        game_with(
+            rules,
+            &[(13, king(Piece::red())), (30, king(Piece::white()))],
+        )
+    }
+
+    const SHUFFLE: [usize; 8] = [13, 9, 30, 25, 9, 13, 25, 30];
+
+    #[test]
+    fn test_threefold_repetition() {
+        let mut game = shuffling_kings(|_| {});
+        // the starting position is the first to occur a third time
+        for pos in SHUFFLE.iter().cycle().take(15) {
+            game.make_a_move_from_api(*pos).unwrap();
+            assert_eq!(game.outcome(), None);
+        }
+        game.make_a_move_from_api(30).unwrap();
+        assert_eq!(
+            game.outcome(),
+            Some(GameOutcome::Draw(DrawReason::Repetition))
+        );
+        game.undo_last_move().unwrap();
+        assert_eq!(game.outcome(), None);
+    }
+
+    #[test]
+    fn test_quiet_turn_limit() {
+        let mut game = shuffling_kings(|rules| rules.quiet_turn_limit = Some(4));
+        for pos in &SHUFFLE[..6] {
+            game.make_a_move_from_api(*pos).unwrap();
+        }
+        assert_eq!(game.quiet_turns(), 3);
+        assert_eq!(game.outcome(), None);
+        for pos in &SHUFFLE[6..] {
+            game.make_a_move_from_api(*pos).unwrap();
+        }
+        assert_eq!(
+            game.outcome(),
+            Some(GameOutcome::Draw(DrawReason::MoveLimit))
+        );
+
+        // moving a man starts the count again
+        let mut game = game_with(
+            Ruleset {
+                quiet_turn_limit: Some(2),
+                ..Ruleset::english()
+            },
+            &[(13, king(Piece::red())), (30, Piece::white())],
+        );
+        for pos in [13, 9, 30, 25, 9, 13] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert_eq!(game.quiet_turns(), 1);
+        assert_eq!(game.outcome(), None);
+    }
+
+    #[test]
+    fn test_claim_draw_after_insufficient_progress() {
+        let mut game = shuffling_kings(|rules| rules.quiet_turn_claim = Some(2));
+        for pos in &SHUFFLE[..2] {
+            game.make_a_move_from_api(*pos).unwrap();
+        }
+        assert!(!game.claim_draw());
+        game.make_a_move_from_api(30).unwrap();
+        assert!(!game.claim_draw(), "claims are made at the start of a turn");
+        game.make_a_move_from_api(25).unwrap();
+        assert!(game.claim_draw());
+        assert_eq!(
+            game.outcome(),
+            Some(GameOutcome::Draw(DrawReason::InsufficientProgress))
+        );
+        game.undo_last_move().unwrap();
+        assert_eq!(game.outcome(), None);
+    }
+
+    #[test]
+    fn test_resign() {
+        let mut game = Checkers::new(None);
+        game.start_headless();
+        game.resign();
+        assert_eq!(
+            game.outcome(),
+            Some(GameOutcome::Win {
+                winner: Player::White,
+                reason: WinReason::Resignation
+            })
+        );
+        assert_eq!(
+            game.history.actions,
+            vec![(0, GameAction::Resign(Player::Red))]
+        );
+    }
+
+    #[test]
+    fn test_draw_offers() {
+        let mut game = Checkers::new(None);
+        game.start_headless();
+        assert!(game.offer_draw());
+        assert!(!game.offer_draw(), "one offer per turn");
+        assert_eq!(game.draw_offer(), None);
+        for pos in [9, 13] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert_eq!(game.draw_offer(), Some(Player::Red));
+
+        // moving declines the offer
+        for pos in [21, 17] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert_eq!(game.draw_offer(), None);
+        assert!(!game.accept_draw());
+        assert_eq!(
+            game.history.last_action(),
+            Some(&(1, GameAction::DeclineDraw(Player::White)))
+        );
+
+        // undoing white's move brings the offer back, and accepting it ends the game
+        game.undo_last_move().unwrap();
+        game.undo_last_move().unwrap();
+        assert_eq!(game.draw_offer(), Some(Player::Red));
+        assert!(game.accept_draw());
+        assert_eq!(
+            game.outcome(),
+            Some(GameOutcome::Draw(DrawReason::Agreement))
+        );
+        assert_eq!(
+            game.history.actions,
+            vec![
+                (0, GameAction::OfferDraw(Player::Red)),
+                (1, GameAction::AcceptDraw(Player::White))
+            ]
+        );
+
+        game.undo_last_move().unwrap();
+        assert_eq!(game.outcome(), None);
+        assert_eq!(game.history.actions.len(), 1);
+    }
+
+    #[test]
+    fn test_bot_assessment() {
+        let mut game = Checkers::new(None);
+        game.start_headless();
+        game.act_on_assessment(Assessment::WantsDraw);
+        for pos in [9, 13] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        game.act_on_assessment(Assessment::PlayOn);
+        assert_eq!(game.draw_offer(), None);
+        assert_eq!(
+            game.history.last_action(),
+            Some(&(1, GameAction::DeclineDraw(Player::White)))
+        );
+        game.act_on_assessment(Assessment::Resign);
+        assert_eq!(game.outcome().and_then(|o| o.winner()), Some(Player::Red));
+    }
+
+    #[test]
+    fn test_headless_turns() {
+        let mut game = Checkers::new(None);
+        assert_eq!(game.side_to_move(), Player::Red);
+        assert_eq!(game.legal_turns().len(), 7);
+        assert!(game.legal_turns().contains(&Turn::new(vec![9, 13])));
+
+        let board = game.board().clone();
+        assert_eq!(
+            game.apply_turn(&Turn::new(vec![9])),
+            Err(IllegalMove::TurnUnfinished.into())
+        );
+        assert_eq!(
+            game.apply_turn(&Turn::new(vec![9, 17])),
+            Err(IllegalMove::NotADestination { from: 9, to: 17 }.into())
+        );
+        assert_eq!(
+            game.apply_turn(&Turn::new(vec![9, 13, 17])),
+            Err(IllegalMove::TurnFinished.into())
+        );
+        assert_eq!(game.board(), &board);
+        assert_eq!(game.selected_piece(), None);
+
+        game.apply_turn(&Turn::new(vec![9, 13])).unwrap();
+        assert_eq!(game.side_to_move(), Player::White);
+        game.apply_turn(&Turn::new(vec![22, 17])).unwrap();
+        // red must capture, through the whole jump in one turn
+        assert_eq!(game.legal_turns(), vec![Turn::new(vec![13, 22])]);
+        game.apply_turn(&Turn::new(vec![13, 22])).unwrap();
+        assert_eq!(game.side_to_move(), Player::White);
+
+        game.undo().unwrap();
+        assert_eq!(game.side_to_move(), Player::Red);
+        assert_eq!(game.board().get(13), Some(Piece::red()));
+        game.undo().unwrap();
+        game.undo().unwrap();
+        assert_eq!(game.board(), &board);
+        assert_eq!(game.undo(), Err(CheckersError::NothingToUndo));
+    }
+
+    #[test]
+    fn test_events() {
+        let pieces = [
+            (22, Piece::red()),
+            (26, Piece::white()),
+            (12, Piece::white()),
+        ];
+        let mut game = game_with(Ruleset::english(), &pieces);
+        let events = game.subscribe();
+
+        assert!(game.apply_turn(&Turn::new(vec![22])).is_err());
+        assert!(events.try_recv().is_err());
+
+        game.apply_turn(&Turn::new(vec![22, 31])).unwrap();
+        game.resign();
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                GameEvent::PieceSelected { position: 22 },
+                GameEvent::PieceMoved { from: 22, to: 31 },
+                GameEvent::PieceCaptured {
+                    position: 26,
+                    piece: Piece::white()
+                },
+                GameEvent::PieceCrowned { position: 31 },
+                GameEvent::TurnChanged {
+                    player: Player::White
+                },
+                GameEvent::GameOver(GameOutcome::Win {
+                    winner: Player::Red,
+                    reason: WinReason::Resignation
+                }),
+            ]
+        );
+        // copies made for searching do not pass events on
+        game.clone().undo().unwrap();
+        assert!(events.try_recv().is_err());
+
+        game.undo().unwrap();
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                GameEvent::StepUndone,
+                GameEvent::TurnChanged {
+                    player: Player::Red
+                },
+                GameEvent::StepUndone,
+            ]
+        );
+        game.redo().unwrap();
+        assert_eq!(
+            events.try_iter().next(),
+            Some(GameEvent::PieceSelected { position: 22 })
+        );
+    }
+
+    #[test]
+    fn test_illegal_steps_leave_game_unchanged() {
+        let mut game = Checkers::new(None);
+        let board = game.board().clone();
+        assert_eq!(
+            game.make_a_move_from_api(32),
+            Err(IllegalMove::OutOfBounds(32).into())
+        );
+        assert_eq!(
+            game.make_a_move_from_api(0),
+            Err(IllegalMove::NotMovable(0).into())
+        );
+        assert_eq!(
+            game.make_a_move_from_api(21),
+            Err(CheckersError::WrongPlayer {
+                position: 21,
+                owner: Player::White
+            })
+        );
+        assert_eq!(game.undo_last_move(), Err(CheckersError::NothingToUndo));
+        assert_eq!(game.undo_last_turn(), Err(CheckersError::NothingToUndo));
+
+        game.make_a_move_from_api(9).unwrap();
+        assert_eq!(
+            game.make_a_move_from_api(18),
+            Err(IllegalMove::NotADestination { from: 9, to: 18 }.into())
+        );
+        assert_eq!(game.selected_piece(), Some(9));
+        assert_eq!(game.board(), &board);
+        assert_eq!(
+            Checkers::custom_board(9, None).err(),
+            Some(CheckersError::InvalidBoardSize(9))
+        );
+    }
+
+    #[test]
+    fn test_undo_unfinished_turn() {
+        let pieces = [(1, Piece::red()), (5, Piece::white()), (13, Piece::white())];
+        let mut game = game_with(Ruleset::english(), &pieces);
+        for pos in [1, 8] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        assert_eq!(game.side_to_move(), Player::Red);
+        game.undo().unwrap();
+        assert_eq!(game.selected_piece(), None);
+        assert_eq!(game.legal_turns(), vec![Turn::new(vec![1, 8, 17])]);
+    }
+
+    #[test]
+    fn test_no_legal_turns_once_over() {
+        let mut game = Checkers::new(None);
+        game.resign();
+        assert!(game.legal_turns().is_empty());
+        assert!(matches!(
+            game.apply_turn(&Turn::new(vec![9, 13])),
+            Err(CheckersError::IllegalMove(IllegalMove::GameOver(_)))
+        ));
+    }
+
+    /// Everything about a game that undoing and redoing has to restore.
+    fn snapshot(game: &Checkers) -> (Board, Player, Option<usize>, Vec<usize>, usize) {
+        (
+            game.board().clone(),
+            game.current_player(),
+            game.selected_piece(),
+            game.selectable_positions().to_vec(),
+            game.turns_played(),
+        )
+    }
+
+    /// A game of up to `steps` random steps, with a snapshot from before the first step and
+    /// after every step.
+    #[allow(clippy::type_complexity)]
+    fn random_game(
+        rules: Ruleset,
+        steps: usize,
+        rng: &mut XorShift,
+    ) -> (
+        Checkers,
+        Vec<(Board, Player, Option<usize>, Vec<usize>, usize)>,
+    ) {
+        let mut game = Checkers::with_rules(rules, None).unwrap();
+        let mut snapshots = vec![snapshot(&game)];
+        for _ in 0..steps {
+            let choices = game.selectable_positions().to_vec();
+            if choices.is_empty() {
+                break;
+            }
+            game.make_a_move_from_api(choices[rng.next_usize(choices.len())])
+                .unwrap();
+            snapshots.push(snapshot(&game));
+        }
+        (game, snapshots)
+    }
+
+    const UNDO_RULES: [fn() -> Ruleset; 5] = [
+        Ruleset::english,
+        Ruleset::international,
+        Ruleset::russian,
+        Ruleset::turkish,
+        Ruleset::casual,
+    ];
+
+    #[test]
+    fn test_stepping_back_and_forward_restores_every_step() {
+        let mut rng = XorShift(7);
+        for rules in UNDO_RULES {
+            for _ in 0..8 {
+                let (mut game, snapshots) = random_game(rules(), 80, &mut rng);
+                let steps = snapshots.len() - 1;
+                for expected in snapshots.iter().rev().skip(1) {
+                    game.step_back(1).unwrap();
+                    assert_eq!(&snapshot(&game), expected);
+                }
+                assert_eq!(game.step_back(1), Err(CheckersError::NothingToUndo));
+                assert_eq!(
+                    game.step_forward(steps + 1),
+                    Err(CheckersError::NothingToRedo)
+                );
+                assert_eq!(snapshot(&game), snapshots[0]);
+
+                for expected in snapshots.iter().skip(1) {
+                    game.step_forward(1).unwrap();
+                    assert_eq!(&snapshot(&game), expected);
+                }
+                let back = rng.next_usize(steps + 1);
+                game.step_back(back).unwrap();
+                assert_eq!(snapshot(&game), snapshots[steps - back]);
+                game.step_forward(back).unwrap();
+                assert_eq!(snapshot(&game), snapshots[steps]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_go_to_turn_restores_turn_starts() {
+        let mut rng = XorShift(11);
+        for rules in UNDO_RULES {
+            let (mut game, snapshots) = random_game(rules(), 80, &mut rng);
+            let end = snapshot(&game);
+            let turn_starts: Vec<_> =
+                snapshots
+                    .iter()
+                    .filter(|s| s.2.is_none())
+                    .fold(Vec::new(), |mut starts, s| {
+                        if starts.len() == s.4 {
+                            starts.push(s.clone());
+                        }
+                        starts
+                    });
+            let turns = game.turns_played();
+            for _ in 0..20 {
+                let turn = rng.next_usize(turns + 1);
+                game.go_to_turn(turn).unwrap();
+                assert_eq!(snapshot(&game), turn_starts[turn]);
+            }
+            assert_eq!(
+                game.go_to_turn(turns + 1),
+                Err(CheckersError::NothingToRedo)
+            );
+            game.go_to_turn(turns).unwrap();
+            game.step_forward(game.history.undone.len()).unwrap();
+            assert_eq!(snapshot(&game), end);
+        }
+    }
+
+    #[test]
+    fn test_rejected_turn_keeps_redo() {
+        let mut game = Checkers::new(None);
+        let turns = game.legal_turns();
+        game.apply_turn(&turns[0]).unwrap();
+        let after = game.board().clone();
+        game.undo().unwrap();
+
+        let other = turns.iter().find(|t| t.steps()[0] != turns[0].steps()[0]);
+        let unfinished = Turn::new(vec![other.unwrap().steps()[0]]);
+        assert_eq!(
+            game.apply_turn(&unfinished),
+            Err(IllegalMove::TurnUnfinished.into())
+        );
+        game.redo().unwrap();
+        assert_eq!(game.board(), &after);
+        assert_eq!(game.redo(), Err(CheckersError::NothingToRedo));
+    }
+
+    #[test]
+    fn test_redo_is_forgotten_after_a_different_step() {
+        let mut game = Checkers::new(None);
+        game.apply_turn(&Turn::new(vec![9, 13])).unwrap();
+        game.apply_turn(&Turn::new(vec![22, 17])).unwrap();
+        game.undo().unwrap();
+        game.undo().unwrap();
+        assert_eq!(game.turns_played(), 0);
+
+        // playing the same turn again keeps the rest
+        game.apply_turn(&Turn::new(vec![9, 13])).unwrap();
+        game.redo().unwrap();
+        assert_eq!(game.board().get(17), Some(Piece::white()));
+
+        game.undo().unwrap();
+        game.apply_turn(&Turn::new(vec![22, 18])).unwrap();
+        assert_eq!(game.redo(), Err(CheckersError::NothingToRedo));
+        assert_eq!(game.go_to_turn(3), Err(CheckersError::NothingToRedo));
+        assert_eq!(game.turns_played(), 2);
+    }
+
+    #[test]
+    fn test_take_back() {
+        let mut game = Checkers::new(None).with_take_back_limit(Some(1));
+        assert_eq!(game.take_back(), Err(CheckersError::NothingToUndo));
+        for turn in [vec![9, 13], vec![22, 17], vec![13, 22], vec![25, 18]] {
+            game.apply_turn(&Turn::new(turn)).unwrap();
+        }
+        let board = game.board().clone();
+        game.make_a_move_from_api(10).unwrap();
+
+        game.take_back().unwrap();
+        assert_eq!(game.side_to_move(), Player::Red);
+        assert_eq!(game.turns_played(), 2);
+        assert_eq!(game.board().get(22), None);
+        assert_eq!(game.legal_turns(), vec![Turn::new(vec![13, 22])]);
+        assert_eq!(game.take_back(), Err(CheckersError::TakeBackLimit(1)));
+
+        // finished turns cannot be taken back around the limit
+        let taken_back = game.board().clone();
+        assert_eq!(game.undo(), Err(CheckersError::LimitedTakeBacks));
+        assert_eq!(game.undo_last_turn(), Err(CheckersError::LimitedTakeBacks));
+        assert_eq!(game.undo_last_move(), Err(CheckersError::LimitedTakeBacks));
+        assert_eq!(game.go_to_turn(0), Err(CheckersError::LimitedTakeBacks));
+        game.make_a_move_from_api(13).unwrap();
+        assert_eq!(game.step_back(2), Err(CheckersError::LimitedTakeBacks));
+        assert_eq!(game.selected_piece(), Some(13));
+        game.undo().unwrap();
+        assert_eq!(game.board(), &taken_back);
+        assert_eq!(game.turns_played(), 2);
+
+        // the take-back stays in the record even when play goes back further
+        game = game.with_take_back_limit(None);
+        game.undo().unwrap();
+        assert_eq!(
+            game.history.actions,
+            vec![(2, GameAction::TakeBack(Player::Red))]
+        );
+        game.redo().unwrap();
+        game.redo().unwrap();
+        game.redo().unwrap();
+        assert_eq!(game.board(), &board);
+
+        // white has not used its take-back yet
+        game.undo().unwrap();
+        game = game.with_take_back_limit(Some(1));
+        game.take_back().unwrap();
+        assert_eq!(game.side_to_move(), Player::White);
+        assert_eq!(game.turns_played(), 1);
+        assert_eq!(game.history.take_backs(Player::White), 1);
+    }
+
+    #[test]
+    fn test_setup_validates_position() {
+        let mut layout = vec![None; 32];
+        layout[14] = Some(Piece::red());
+        layout[17] = Some(Piece::white());
+        let board = Board::with_layout(8, Movement::Diagonal, layout.clone()).unwrap();
+        let game = Checkers::setup(board.clone(), Player::White, None).unwrap();
+        assert_eq!(game.side_to_move(), Player::White);
+        assert_eq!(game.selectable_positions(), vec![17]);
+        assert!(Checkers::setup(board.clone(), Player::Red, Some(Ruleset::italian())).is_ok());
+
+        let malformed = |board, rules| {
+            matches!(
+                Checkers::setup(board, Player::Red, rules),
+                Err(CheckersError::MalformedPosition(_))
+            )
+        };
+        assert!(malformed(board.clone(), Some(Ruleset::international())));
+        assert!(malformed(board, Some(Ruleset::turkish())));
+        assert!(malformed(
+            Board::with_layout(8, Movement::Diagonal, vec![None; 40]).unwrap(),
+            Some(Ruleset::english())
+        ));
+        // a layout of the wrong length makes a rectangular board, which needs rules for its rows
+        assert!(malformed(
+            Board::with_layout(8, Movement::Diagonal, vec![None; 40]).unwrap(),
+            None
+        ));
+        for (pos, piece) in [(29, Piece::red()), (2, Piece::white())] {
+            let mut on_far_row = layout.clone();
+            on_far_row[pos] = Some(piece);
+            assert!(malformed(
+                Board::with_layout(8, Movement::Diagonal, on_far_row).unwrap(),
+                None
+            ));
+        }
+        let mut crowded = layout.clone();
+        for square in crowded.iter_mut().take(13) {
+            *square = Some(Piece::red());
+        }
+        assert!(malformed(
+            Board::with_layout(8, Movement::Diagonal, crowded).unwrap(),
+            None
+        ));
+
+        // a board of another size is played by the default rules resized to fit it
+        let game = Checkers::setup(Board::new(10).unwrap(), Player::Red, None).unwrap();
+        assert_eq!(game.rules().board_size, 10);
+        assert_eq!(game.legal_turns().len(), 9);
+
+        // a board for orthogonal moves is played by the turkish rules
+        let board = Board::for_rules(&Ruleset::turkish()).unwrap();
+        let game = Checkers::setup(board, Player::White, None).unwrap();
+        assert_eq!(game.rules(), &Ruleset::turkish());
+    }
 }
@@ -1 +1,17 @@
 pub(crate) const DIRECTIONS: [(usize, usize); 4] = [(1, 1), (1, !0), (!0, !0), (!0, 1)];
+
+/// Red's forward, the two sideways directions, then white's forward, so men of either player
+/// take three in a row.
+pub(crate) const ORTHOGONAL_DIRECTIONS: [(usize, usize); 4] = [(1, 0), (0, 1), (0, !0), (!0, 0)];
+
+/// Number of [`crate::checkers::Checkers::make_a_move_from_api`] steps the bot searches ahead.
+pub(crate) const DEFAULT_SEARCH_DEPTH: usize = 10;
+
+/// Occurrences of the same position, with the same player to move, that draw the game.
+pub(crate) const REPETITION_LIMIT: usize = 3;
+
+/// Turns a bot waits after offering a draw before it offers another.
+pub(crate) const BOT_DRAW_OFFER_INTERVAL: usize = 20;
+
+/// Score of a won position, large enough that no material count can reach it.
+pub(crate) const WIN_SCORE: i64 = 1_000_000;
@@ -0,0 +1,115 @@
+use std::fmt::Display;
+
+use crate::{outcome::GameOutcome, player::Player};
+
+/// Everything that can go wrong when setting up or playing a game through the library.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CheckersError {
+    /// Boards need an even number of at least 6 rows and columns.
+    InvalidBoardSize(usize),
+    IllegalMove(IllegalMove),
+    /// There is no move left in the history to take back.
+    NothingToUndo,
+    /// No move has been taken back since the last one was played.
+    NothingToRedo,
+    /// The player has used up the take-backs allowed in the game.
+    TakeBackLimit(usize),
+    /// Take-backs are limited in the game, so finished turns can only be taken back with
+    /// [`crate::checkers::Checkers::take_back`].
+    LimitedTakeBacks,
+    /// The piece on the position belongs to the player who is not to move.
+    WrongPlayer {
+        position: usize,
+        owner: Player,
+    },
+    /// A board layout or encoding that does not describe a position.
+    MalformedPosition(String),
+    /// A network for boards of `network` positions was used with a board of `board` positions.
+    NetworkMismatch {
+        network: usize,
+        board: usize,
+    },
+}
+
+/// Why a step or turn was refused.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IllegalMove {
+    /// The position is not on the board.
+    OutOfBounds(usize),
+    /// There is no piece of the player to move on the position that can move this turn.
+    NotMovable(usize),
+    /// The selected piece cannot move to the position.
+    NotADestination {
+        from: usize,
+        to: usize,
+    },
+    /// The steps of a turn stopped before the turn was over.
+    TurnUnfinished,
+    /// The steps of a turn went on after the turn was over.
+    TurnFinished,
+    GameOver(GameOutcome),
+}
+
+impl std::error::Error for CheckersError {}
+
+impl Display for CheckersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckersError::InvalidBoardSize(size) => write!(
+                f,
+                "invalid board size {}, it must be even and at least 6",
+                size
+            ),
+            CheckersError::IllegalMove(reason) => write!(f, "illegal move: {}", reason),
+            CheckersError::NothingToUndo => write!(f, "there is nothing to undo"),
+            CheckersError::NothingToRedo => write!(f, "there is nothing to redo"),
+            CheckersError::TakeBackLimit(limit) => {
+                write!(f, "only {} take-backs are allowed", limit)
+            }
+            CheckersError::LimitedTakeBacks => write!(
+                f,
+                "take-backs are limited, finished turns can only be taken back with a take-back"
+            ),
+            CheckersError::WrongPlayer { position, owner } => write!(
+                f,
+                "the piece on position {} belongs to {}, who is not to move",
+                position, owner
+            ),
+            CheckersError::MalformedPosition(reason) => write!(f, "malformed position: {}", reason),
+            CheckersError::NetworkMismatch { network, board } => write!(
+                f,
+                "the network is for boards of {} positions, not {}",
+                network, board
+            ),
+        }
+    }
+}
+
+impl Display for IllegalMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IllegalMove::OutOfBounds(position) => {
+                write!(f, "position {} is not on the board", position)
+            }
+            IllegalMove::NotMovable(position) => {
+                write!(
+                    f,
+                    "there is no piece on position {} that can move",
+                    position
+                )
+            }
+            IllegalMove::NotADestination { from, to } => {
+                write!(f, "the piece on position {} cannot move to {}", from, to)
+            }
+            IllegalMove::TurnUnfinished => write!(f, "the turn is not finished"),
+            IllegalMove::TurnFinished => write!(f, "the turn ended before every step was played"),
+            IllegalMove::GameOver(outcome) => write!(f, "the game is over: {}", outcome),
+        }
+    }
+}
+
+impl From<IllegalMove> for CheckersError {
+    fn from(reason: IllegalMove) -> Self {
+        CheckersError::IllegalMove(reason)
+    }
+}
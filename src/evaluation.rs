@@ -0,0 +1,192 @@
+use std::{fmt::Display, fs, path::Path, str::FromStr};
+
+use anyhow::{anyhow, Context};
+
+use crate::{board::Board, player::Player};
+
+/// Weights file loaded by the bot at startup, as written by [`crate::tuner::tune_file`].
+pub const WEIGHTS_FILE: &str = "weights.txt";
+
+/// Number of features in [`Evaluator::features`].
+pub const FEATURE_COUNT: usize = 5;
+
+/// Names of the features, used as keys in weight files.
+pub const FEATURE_NAMES: [&str; FEATURE_COUNT] =
+    ["men", "kings", "advancement", "back_rank", "center"];
+
+/// Score difference, in evaluation units, that [`win_probability`] maps to odds of e to 1.
+pub(crate) const SIGMOID_SCALE: f64 = 100.0;
+
+const DEFAULT_WEIGHTS: [i64; FEATURE_COUNT] = [100, 150, 2, 10, 5];
+
+/// A linear evaluation: the score of a position is the sum of each feature multiplied by its
+/// weight. Every feature is the red value minus the white value, so a positive score favors red.
+///
+/// | feature       | counts                                             |
+/// |---------------|----------------------------------------------------|
+/// | `men`         | men                                                |
+/// | `kings`       | kings                                              |
+/// | `advancement` | rows each man has advanced from its own back row   |
+/// | `back_rank`   | men still on their own back row                    |
+/// | `center`      | pieces in the middle half of the board             |
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Evaluator {
+    weights: [i64; FEATURE_COUNT],
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Self {
+            weights: DEFAULT_WEIGHTS,
+        }
+    }
+}
+
+impl Evaluator {
+    pub fn new(weights: [i64; FEATURE_COUNT]) -> Self {
+        Self { weights }
+    }
+
+    pub fn weights(&self) -> &[i64; FEATURE_COUNT] {
+        &self.weights
+    }
+
+    /// Loads weights written by [`Evaluator::save`]. Features missing from the file keep their
+    /// default weight.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        fs::read_to_string(path)
+            .with_context(|| format!("failed to read weights from {}", path.display()))?
+            .parse()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.to_string())
+            .with_context(|| format!("failed to write weights to {}", path.display()))
+    }
+
+    /// Score of the board from the point of view of `player`.
+    pub fn evaluate(&self, board: &Board, player: Player) -> i64 {
+        let score = Self::score(&self.weights, &Self::features(board));
+        match player {
+            Player::Red => score,
+            Player::White => -score,
+        }
+    }
+
+    /// Score of the board from the point of view of `player` for giveaway games, where every
+    /// piece still on the board is a burden. Only the material weights are used, since where
+    /// the remaining pieces stand matters little when they are to be lost.
+    pub fn evaluate_giveaway(&self, board: &Board, player: Player) -> i64 {
+        let features = Self::features(board);
+        let material = self.weights[0] * features[0] + self.weights[1] * features[1];
+        match player {
+            Player::Red => -material,
+            Player::White => material,
+        }
+    }
+
+    pub(crate) fn score(weights: &[i64; FEATURE_COUNT], features: &[i64; FEATURE_COUNT]) -> i64 {
+        weights.iter().zip(features).map(|(w, f)| w * f).sum()
+    }
+
+    /// The feature values of the board, red minus white.
+    pub fn features(board: &Board) -> [i64; FEATURE_COUNT] {
+        let (rows, columns) = (board.rows(), board.size());
+        let mut features = [0; FEATURE_COUNT];
+        for (player, sign) in [(Player::Red, 1), (Player::White, -1)] {
+            for pos in board.get_player_piece_positions(player) {
+                let (row, col) = board.to_coord(pos);
+                let (advanced, backward) = match player {
+                    Player::Red => (row, !0),
+                    Player::White => (rows - 1 - row, 1),
+                };
+                if board.get(pos).is_some_and(|p| p.is_king()) {
+                    features[1] += sign;
+                } else {
+                    features[0] += sign;
+                    features[2] += sign * advanced as i64;
+                    // a man with nothing behind it is on its own back row
+                    let on_back_row = board
+                        .movement()
+                        .directions()
+                        .iter()
+                        .filter(|d| d.0 == backward)
+                        .all(|d| board.neighbor(pos, *d).is_none());
+                    if on_back_row {
+                        features[3] += sign;
+                    }
+                }
+                if (rows / 4..rows - rows / 4).contains(&row)
+                    && (columns / 4..columns - columns / 4).contains(&col)
+                {
+                    features[4] += sign;
+                }
+            }
+        }
+        features
+    }
+}
+
+/// Probability of red winning predicted from a red-positive score.
+pub(crate) fn win_probability(score: f64) -> f64 {
+    1.0 / (1.0 + (-score / SIGMOID_SCALE).exp())
+}
+
+impl Display for Evaluator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, weight) in FEATURE_NAMES.iter().zip(self.weights) {
+            writeln!(f, "{} {}", name, weight)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Evaluator {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = DEFAULT_WEIGHTS;
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (name, weight) = line
+                .split_once(' ')
+                .ok_or_else(|| anyhow!("expected \"name weight\", got {:?}", line))?;
+            let i = FEATURE_NAMES
+                .iter()
+                .position(|n| *n == name)
+                .ok_or_else(|| anyhow!("unknown feature {:?}", name))?;
+            weights[i] = weight
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid weight for {}", name))?;
+        }
+        Ok(Self { weights })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_position_is_even() {
+        let evaluator = Evaluator::default();
+        assert_eq!(evaluator.evaluate(&Board::new(8).unwrap(), Player::Red), 0);
+        assert_eq!(
+            Evaluator::features(&Board::new(8).unwrap()),
+            [0; FEATURE_COUNT]
+        );
+    }
+
+    #[test]
+    fn test_weights_round_trip() {
+        let evaluator = Evaluator::new([1, -2, 3, 40, 500]);
+        assert_eq!(
+            evaluator.to_string().parse::<Evaluator>().unwrap(),
+            evaluator
+        );
+        assert_eq!("kings 7".parse::<Evaluator>().unwrap().weights()[1], 7);
+        assert!("queens 7".parse::<Evaluator>().is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use std::{fmt::Debug, sync::mpsc::Sender};
+
+use crate::{board::Board, outcome::GameOutcome, piece::Piece, player::Player};
+
+/// Something that happened in a [`crate::checkers::Checkers`] game, as told to its
+/// [`GameObserver`]s. Positions are board positions, see [`Board::to_coord`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameEvent {
+    PieceSelected {
+        position: usize,
+    },
+    PieceMoved {
+        from: usize,
+        to: usize,
+    },
+    PieceCaptured {
+        position: usize,
+        piece: Piece,
+    },
+    /// An opponent's piece was removed for not capturing when it could have.
+    PieceHuffed {
+        position: usize,
+        piece: Piece,
+    },
+    PieceCrowned {
+        position: usize,
+    },
+    /// The last step was taken back, so the board is as it was before that step. Taking back a
+    /// whole turn, or going to another turn, sends one for every step taken back, and steps
+    /// played again are sent as they were the first time.
+    StepUndone,
+    /// The turn passed to `player`.
+    TurnChanged {
+        player: Player,
+    },
+    GameOver(GameOutcome),
+}
+
+/// Receives the events of a game it was added to with
+/// [`crate::checkers::Checkers::add_observer`], to show or record them however it likes.
+pub trait GameObserver: Debug {
+    /// Called after `event` happened, with the board as it is now.
+    fn notify(&mut self, event: &GameEvent, board: &Board);
+}
+
+/// Sends every event down the channel. Events are dropped once the receiver is gone.
+impl GameObserver for Sender<GameEvent> {
+    fn notify(&mut self, event: &GameEvent, _board: &Board) {
+        let _ = self.send(*event);
+    }
+}
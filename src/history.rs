@@ -1,54 +1,57 @@
-use crate::{player::Player, r#move::Move};
+use crate::{outcome::GameAction, player::Player, r#move::Move};
 
-#[derive(Clone, Debug)]
-pub struct History(pub Vec<(Player, Vec<Move>)>);
+#[derive(Clone, Default, Debug)]
+pub struct History {
+    /// The steps of every turn played, in order.
+    pub turns: Vec<(Player, Vec<Move>)>,
+    /// Everything done besides moving, each with the number of turns finished before it.
+    pub actions: Vec<(usize, GameAction)>,
+    /// Steps taken back, the most recent last, until a different step is played.
+    pub undone: Vec<Move>,
+}
 
 impl History {
     pub fn get_last_player(&self) -> Option<Player> {
-        self.0.last().and_then(|t| Some(t.0))
+        self.turns.last().map(|t| t.0)
     }
 
     pub fn get_last_turn(&self) -> Option<&Vec<Move>> {
-        self.0.last().and_then(|t| Some(&t.1))
-    }
-
-    pub fn pop_last_turn(&mut self) -> Option<Vec<Move>> {
-        self.0.pop().and_then(|t| Some(t.1))
+        self.turns.last().map(|t| &t.1)
     }
 
     pub fn get_last_move(&self) -> Option<&Move> {
-        self.0.last().and_then(|t| t.1.last())
+        self.turns.last().and_then(|t| t.1.last())
     }
 
+    /// Removes the last step, keeping it to be played again with [`History::next_undone`].
     pub fn pop_last_move(&mut self) -> Option<Move> {
-        self.0.last_mut().map(|v| v.1.pop()).unwrap_or(None)
+        let last_move = self.turns.last_mut().and_then(|v| v.1.pop());
+        if self.turns.last().is_some_and(|t| t.1.is_empty()) {
+            self.turns.pop();
+        }
+        if let Some(m) = &last_move {
+            self.undone.push(m.clone());
+        }
+        last_move
     }
 
-    pub fn started_last_turn_as_king(&self) -> bool {
-        self.0
-            .last()
-            .and_then(|t| t.1.first().and_then(|m| Some(m.started_king())))
-            .unwrap_or(false)
+    /// The step that was taken back most recently.
+    pub fn next_undone(&self) -> Option<&Move> {
+        self.undone.last()
     }
 
-    pub fn started_last_move_as_king(&self) -> bool {
-        self.0
-            .last()
-            .and_then(|t| t.1.last().and_then(|m| Some(m.started_king())))
-            .unwrap_or(false)
+    /// Number of steps played, over every turn.
+    pub fn step_count(&self) -> usize {
+        self.turns.iter().map(|t| t.1.len()).sum()
     }
 
-    pub fn ended_last_turn_as_king(&self) -> bool {
-        self.0
+    pub fn started_last_move_as_king(&self) -> bool {
+        self.turns
             .last()
-            .and_then(|t| t.1.last().and_then(|m| Some(m.ended_king())))
+            .and_then(|t| t.1.last().map(|m| m.started_king()))
             .unwrap_or(false)
     }
 
-    pub fn ended_last_move_as_king(&self) -> bool {
-        self.ended_last_turn_as_king()
-    }
-
     pub fn last_move_was_capture(&self) -> bool {
         if let Some(last_move) = self.get_last_move() {
             last_move.is_capture()
@@ -57,19 +60,52 @@ impl History {
         }
     }
 
+    pub fn last_move_was_select(&self) -> bool {
+        self.get_last_move().is_some_and(|m| m.is_select())
+    }
+
+    pub fn last_move_was_huff(&self) -> bool {
+        self.get_last_move().is_some_and(|m| m.is_huff())
+    }
+
+    /// Adds a step to the turn of `player`. Steps taken back can only be played again as
+    /// long as the same steps are played in the meantime.
     pub fn push(&mut self, player: Player, r#move: Move) {
+        if self.undone.last() == Some(&r#move) {
+            self.undone.pop();
+        } else {
+            self.undone.clear();
+        }
         if self.get_last_player().unwrap_or(player.other()) == player {
-            self.0.last_mut().map(|(_, moves)| {
+            if let Some((_, moves)) = self.turns.last_mut() {
                 moves.push(r#move);
-            });
+            }
         } else {
-            self.0.push((player, vec![r#move]));
+            self.turns.push((player, vec![r#move]));
         }
     }
-}
 
-impl Default for History {
-    fn default() -> Self {
-        Self(Default::default())
+    pub fn record_action(&mut self, turns_finished: usize, action: GameAction) {
+        self.actions.push((turns_finished, action));
+    }
+
+    pub fn last_action(&self) -> Option<&(usize, GameAction)> {
+        self.actions.last()
+    }
+
+    /// Forgets the actions taken after `turns_finished` turns, for when those turns are undone.
+    /// Take-backs are kept.
+    pub fn truncate_actions(&mut self, turns_finished: usize) {
+        self.actions.retain(|(turn, action)| {
+            *turn <= turns_finished || matches!(action, GameAction::TakeBack(_))
+        });
+    }
+
+    /// Number of take-backs by `player`.
+    pub fn take_backs(&self, player: Player) -> usize {
+        self.actions
+            .iter()
+            .filter(|(_, action)| *action == GameAction::TakeBack(player))
+            .count()
     }
 }
@@ -0,0 +1,417 @@
+use std::path::PathBuf;
+
+use crate::{
+    bot::Bot,
+    checkers::Checkers,
+    evaluation::{win_probability, Evaluator, FEATURE_COUNT, SIGMOID_SCALE},
+    minmax::MinMax,
+    outcome::{Adjudication, GameOutcome},
+    player::Player,
+    turn::Turn,
+};
+
+/// Settings for [`train`].
+#[derive(Clone, Debug)]
+pub struct TdConfig {
+    /// Self-play games to learn from.
+    pub games: usize,
+    /// How far credit for a result reaches back through earlier positions, from 0 to 1.
+    pub lambda: f64,
+    pub learning_rate: f64,
+    /// Chance of playing a random turn instead of the best one, so more positions are seen.
+    pub exploration: f64,
+    /// Games still going after this many turns are scored as draws.
+    pub max_turns: usize,
+    /// Games between checkpoints, each checkpoint saves the weights and measures strength.
+    /// With 0 the only checkpoint is after the last game.
+    pub checkpoint_every: usize,
+    pub checkpoint_path: Option<PathBuf>,
+    /// Games played against the baseline at each checkpoint, as pairs where each side plays
+    /// both colors from the same randomly chosen opening.
+    pub match_games: usize,
+    /// Random turns played at the start of each pair of match games.
+    pub opening_turns: usize,
+    /// Search depth of both bots in the checkpoint match.
+    pub match_depth: usize,
+    /// Ends checkpoint match games early once one side is clearly winning.
+    pub adjudication: Option<Adjudication>,
+    pub seed: u64,
+}
+
+impl Default for TdConfig {
+    fn default() -> Self {
+        Self {
+            games: 5000,
+            lambda: 0.7,
+            learning_rate: 500.0,
+            exploration: 0.05,
+            max_turns: 150,
+            checkpoint_every: 500,
+            checkpoint_path: None,
+            match_games: 20,
+            opening_turns: 2,
+            match_depth: 4,
+            adjudication: None,
+            seed: 0x5eed,
+        }
+    }
+}
+
+/// Strength of the learned weights at a checkpoint.
+#[derive(Clone, Debug)]
+pub struct Progress {
+    pub games_played: usize,
+    pub evaluator: Evaluator,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Progress {
+    /// Points scored against the baseline, counting a draw as half a win, from 0 to 1.
+    pub fn score(&self) -> f64 {
+        let games = self.wins + self.draws + self.losses;
+        if games == 0 {
+            0.5
+        } else {
+            (self.wins as f64 + self.draws as f64 / 2.0) / games as f64
+        }
+    }
+}
+
+/// Learns evaluation weights with TD(lambda) by having the bot play itself.
+///
+/// The learned value of a position is the predicted chance of red winning, the
+/// [`win_probability`] of the linear score, so the weights stay in the same units as the
+/// evaluator used by [`MinMax`]. Every `checkpoint_every` games the weights are written to
+/// `checkpoint_path` and played against a [`MinMax`] with the default weights, and the result
+/// is passed to `report`.
+pub fn train(
+    config: &TdConfig,
+    start: Evaluator,
+    mut report: impl FnMut(&Progress),
+) -> anyhow::Result<Evaluator> {
+    let mut weights = start.weights().map(|w| w as f64);
+    let mut rng = XorShift(config.seed.max(1));
+    for game_number in 1..=config.games {
+        self_play_game(config, &mut weights, &mut rng);
+        if game_number.checked_rem(config.checkpoint_every) == Some(0)
+            || game_number == config.games
+        {
+            let evaluator = Evaluator::new(weights.map(|w| w.round() as i64));
+            if let Some(path) = &config.checkpoint_path {
+                evaluator.save(path)?;
+            }
+            let progress = play_baseline_match(config, game_number, evaluator, &mut rng);
+            report(&progress);
+        }
+    }
+    Ok(Evaluator::new(weights.map(|w| w.round() as i64)))
+}
+
+fn value(weights: &[f64; FEATURE_COUNT], features: &[i64; FEATURE_COUNT]) -> f64 {
+    let score: f64 = weights
+        .iter()
+        .zip(features)
+        .map(|(w, f)| w * *f as f64)
+        .sum();
+    win_probability(score)
+}
+
+fn self_play_game(config: &TdConfig, weights: &mut [f64; FEATURE_COUNT], rng: &mut XorShift) {
+    let mut game = Checkers::default();
+    game.start_headless();
+    let mut eligibility = [0.0; FEATURE_COUNT];
+    let mut features = Evaluator::features(game.board());
+    let mut turn = 0;
+    loop {
+        let turns = game.legal_turns();
+        // the game's own rules decide when it is over, so repetitions draw as they would in play
+        let result = match game.outcome().map(|o| o.winner()) {
+            Some(Some(Player::Red)) => Some(1.0),
+            Some(Some(Player::White)) => Some(0.0),
+            Some(None) => Some(0.5),
+            None if turn >= config.max_turns => Some(0.5),
+            None => None,
+        };
+
+        let current_value = value(weights, &features);
+        let (next_features, next_value) = if let Some(result) = result {
+            (features, result)
+        } else {
+            let steps = if rng.next_f64() < config.exploration {
+                &turns[rng.next_usize(turns.len())]
+            } else {
+                choose_greedy(&mut game, &turns, weights)
+            };
+            game.apply_turn(steps).expect("legal turns can be applied");
+            let next_features = Evaluator::features(game.board());
+            (next_features, value(weights, &next_features))
+        };
+
+        // TD(lambda): move every earlier prediction towards the new one
+        let gradient_scale = current_value * (1.0 - current_value) / SIGMOID_SCALE;
+        for (e, f) in eligibility.iter_mut().zip(features) {
+            *e = config.lambda * *e + gradient_scale * f as f64;
+        }
+        let error = next_value - current_value;
+        for (w, e) in weights.iter_mut().zip(eligibility) {
+            *w += config.learning_rate * error * e;
+        }
+
+        if result.is_some() {
+            return;
+        }
+        features = next_features;
+        turn += 1;
+    }
+}
+
+/// The turn leading to the best value for the player to move.
+fn choose_greedy<'a>(
+    game: &mut Checkers,
+    turns: &'a [Turn],
+    weights: &[f64; FEATURE_COUNT],
+) -> &'a Turn {
+    let sign = match game.current_player() {
+        Player::Red => 1.0,
+        Player::White => -1.0,
+    };
+    turns
+        .iter()
+        .map(|turn| {
+            game.apply_turn(turn).expect("legal turns can be applied");
+            let v = sign * value(weights, &Evaluator::features(game.board()));
+            game.undo().expect("a turn was just played");
+            (turn, v)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(turn, _)| turn)
+        .expect("turns is not empty")
+}
+
+fn play_baseline_match(
+    config: &TdConfig,
+    games_played: usize,
+    evaluator: Evaluator,
+    rng: &mut XorShift,
+) -> Progress {
+    let learned = MinMax::new(evaluator, 0);
+    let baseline = MinMax::default();
+    let mut progress = Progress {
+        games_played,
+        evaluator,
+        wins: 0,
+        draws: 0,
+        losses: 0,
+    };
+    let mut opening = Checkers::default();
+    for i in 0..config.match_games {
+        if i % 2 == 0 {
+            opening = random_opening(config.opening_turns, rng);
+        }
+        let learned_player = if i % 2 == 0 {
+            Player::Red
+        } else {
+            Player::White
+        };
+        let (red, white): (&dyn Bot, &dyn Bot) = match learned_player {
+            Player::Red => (&learned, &baseline),
+            Player::White => (&baseline, &learned),
+        };
+        match play_bot_game(
+            opening.clone(),
+            red,
+            white,
+            config.match_depth,
+            config.max_turns,
+            config.adjudication,
+            |_| {},
+        )
+        .winner()
+        {
+            Some(winner) if winner == learned_player => progress.wins += 1,
+            Some(_) => progress.losses += 1,
+            None => progress.draws += 1,
+        }
+    }
+    progress
+}
+
+pub(crate) fn random_opening(turns: usize, rng: &mut XorShift) -> Checkers {
+    let mut game = Checkers::default();
+    game.start_headless();
+    for _ in 0..turns {
+        let turns = game.legal_turns();
+        if turns.is_empty() {
+            break;
+        }
+        let turn = &turns[rng.next_usize(turns.len())];
+        game.apply_turn(turn).expect("legal turns can be applied");
+    }
+    game
+}
+
+/// Plays a game between two bots from `game`. Bots may resign or agree a draw at the start of
+/// their turns, and games still going after `max_turns` turns are adjudicated as draws. With an
+/// `adjudication` rule, games one player is clearly winning are given to them early.
+/// `on_turn` sees the game at the start of every turn.
+pub fn play_bot_game(
+    mut game: Checkers,
+    red: &dyn Bot,
+    white: &dyn Bot,
+    depth: usize,
+    max_turns: usize,
+    adjudication: Option<Adjudication>,
+    mut on_turn: impl FnMut(&Checkers),
+) -> GameOutcome {
+    let mut leader = None;
+    let mut turns_ahead = 0;
+    for _ in 0..max_turns {
+        on_turn(&game);
+        if let Some(rule) = adjudication {
+            let new_leader = rule.leader(game.board());
+            turns_ahead = if new_leader == leader {
+                turns_ahead + 1
+            } else {
+                1
+            };
+            leader = new_leader;
+            if leader.is_some() && turns_ahead >= rule.turns {
+                game.adjudicate(leader);
+            }
+        }
+        let player = game.current_player();
+        let bot = match player {
+            Player::Red => red,
+            Player::White => white,
+        };
+        if game.outcome().is_none() {
+            game.act_on_assessment(bot.assess(&game));
+        }
+        if let Some(outcome) = game.outcome() {
+            return outcome;
+        }
+        while game.current_player() == player {
+            let Some(pos) = bot.get_next_move(&game, Some(depth)) else {
+                break;
+            };
+            game.make_a_move_from_api(pos)
+                .expect("bots only pick selectable positions");
+        }
+    }
+    game.adjudicate(None);
+    game.outcome().expect("adjudicated")
+}
+
+/// Small deterministic random number generator, good enough for picking exploration moves.
+pub(crate) struct XorShift(pub(crate) u64);
+
+impl XorShift {
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub(crate) fn next_usize(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board::Board, outcome::WinReason, piece::Piece, ruleset::Movement};
+
+    #[test]
+    fn test_train_reports_and_changes_weights() {
+        let config = TdConfig {
+            games: 4,
+            max_turns: 40,
+            checkpoint_every: 2,
+            match_games: 2,
+            match_depth: 2,
+            ..Default::default()
+        };
+        let mut reports = Vec::new();
+        let start = Evaluator::default();
+        let learned = train(&config, start, |p| reports.push(p.games_played)).unwrap();
+        assert_eq!(reports, vec![2, 4]);
+        assert_ne!(learned, start);
+    }
+
+    #[test]
+    fn test_train_without_checkpoints_reports_at_the_end() {
+        let config = TdConfig {
+            games: 3,
+            max_turns: 20,
+            checkpoint_every: 0,
+            match_games: 2,
+            match_depth: 1,
+            ..Default::default()
+        };
+        let mut reports = Vec::new();
+        train(&config, Evaluator::default(), |p| {
+            reports.push(p.games_played)
+        })
+        .unwrap();
+        assert_eq!(reports, vec![3]);
+    }
+
+    #[test]
+    fn test_bot_game_adjudication() {
+        // red is three kings up, far more than the threshold
+        let mut layout = vec![None; 32];
+        let mut king = Piece::red();
+        king.to_king();
+        for pos in [0, 1, 2, 3] {
+            layout[pos] = Some(king);
+        }
+        layout[31] = Some(Piece::white());
+        let game = Checkers::setup(
+            Board::with_layout(8, Movement::Diagonal, layout).unwrap(),
+            Player::Red,
+            None,
+        )
+        .unwrap();
+        let minmax = MinMax::default();
+        let rule = Adjudication {
+            threshold: 300,
+            turns: 3,
+            evaluator: Evaluator::default(),
+        };
+        let mut turns = 0;
+        let outcome = play_bot_game(game.clone(), &minmax, &minmax, 2, 100, Some(rule), |_| {
+            turns += 1
+        });
+        assert_eq!(
+            outcome,
+            GameOutcome::Win {
+                winner: Player::Red,
+                reason: WinReason::Adjudication
+            }
+        );
+        assert_eq!(turns, 3);
+
+        // the rule's evaluator decides who is ahead, one seeing no difference never adjudicates
+        let blind = Adjudication {
+            evaluator: Evaluator::new([0; FEATURE_COUNT]),
+            ..rule
+        };
+        let outcome = play_bot_game(game, &minmax, &minmax, 2, 100, Some(blind), |_| {});
+        assert_eq!(outcome.winner(), Some(Player::Red));
+        assert!(!matches!(
+            outcome,
+            GameOutcome::Win {
+                reason: WinReason::Adjudication,
+                ..
+            }
+        ));
+    }
+}
@@ -1,15 +1,26 @@
+pub mod bench;
+pub mod board;
+pub mod bot;
 pub mod bot_choice;
 pub mod checkers;
+pub mod error;
+pub mod evaluation;
+pub mod event;
+pub mod learning;
+pub mod minmax;
+pub mod nnue;
+pub mod outcome;
+pub mod perft;
+pub mod piece;
 pub mod player;
+pub mod ruleset;
+pub mod tuner;
+pub mod turn;
 
 #[cfg(feature = "standalone")]
 pub(crate) mod io;
 
-pub(crate) mod board;
-pub(crate) mod bot;
-pub(crate) mod cache;
+pub(crate) mod bitboard;
 pub(crate) mod constants;
 pub(crate) mod history;
-pub(crate) mod minmax;
 pub(crate) mod r#move;
-pub(crate) mod piece;
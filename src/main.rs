@@ -1,6 +1,101 @@
-use kar_checkers_minmax::{bot_choice::BotChoice, checkers::Checkers, player::Player};
+use kar_checkers_minmax::{
+    bench,
+    bot_choice::BotChoice,
+    checkers::Checkers,
+    evaluation::{Evaluator, WEIGHTS_FILE},
+    learning::{self, TdConfig},
+    nnue, perft,
+    player::Player,
+    ruleset::Ruleset,
+    tuner,
+};
 
-fn main() {
-    let mut game = Checkers::new(Some((Player::White, BotChoice::MinMax)));
-    game.start();
+/// Passes over the weights made by `tune` before giving up on further improvement.
+const TUNE_ITERATIONS: usize = 10_000;
+
+/// Search depth of the bots playing the games exported by `export`.
+const EXPORT_DEPTH: usize = 6;
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["tune", positions] => tune(positions, WEIGHTS_FILE)?,
+        ["tune", positions, weights] => tune(positions, weights)?,
+        ["learn", games] => learn(games.parse()?, WEIGHTS_FILE)?,
+        ["learn", games, weights] => learn(games.parse()?, weights)?,
+        ["bench"] => println!("{}", bench::run(bench::DEFAULT_BENCH_DEPTH)),
+        ["bench", depth] => println!("{}", bench::run(depth.parse()?)),
+        ["perft", depth] => println!("{}", perft::perft(&new_game("english")?, depth.parse()?)),
+        ["perft", depth, variant] => {
+            println!("{}", perft::perft(&new_game(variant)?, depth.parse()?))
+        }
+        ["divide", depth] | ["divide", depth, _] => {
+            let game = new_game(args.get(2).map_or("english", String::as_str))?;
+            let mut total = 0;
+            for (turn, count) in perft::divide(&game, depth.parse()?) {
+                let coords: Vec<_> = turn
+                    .steps()
+                    .iter()
+                    .map(|p| game.board().to_coord(*p))
+                    .collect();
+                println!("{:?}: {}", coords, count);
+                total += count;
+            }
+            println!("Total: {}", total);
+        }
+        ["export", games, path] => {
+            let written = nnue::export_training_data(path, games.parse()?, EXPORT_DEPTH, 1)?;
+            println!("{} positions written to {}", written, path);
+        }
+        ["play", variant] => {
+            let rules = Ruleset::named(variant).ok_or_else(|| unknown_variant(variant))?;
+            let mut game = Checkers::with_rules(rules, Some((Player::White, BotChoice::MinMax)))?;
+            game.start();
+        }
+        _ => {
+            let mut game = Checkers::new(Some((Player::White, BotChoice::MinMax)));
+            game.start();
+        }
+    }
+    Ok(())
+}
+
+/// A started game between two players using the rules of `variant`.
+fn new_game(variant: &str) -> anyhow::Result<Checkers> {
+    let rules = Ruleset::named(variant).ok_or_else(|| unknown_variant(variant))?;
+    let mut game = Checkers::with_rules(rules, None)?;
+    game.start_headless();
+    Ok(game)
+}
+
+fn unknown_variant(variant: &str) -> anyhow::Error {
+    anyhow::anyhow!("unknown variant {:?}", variant)
+}
+
+fn tune(positions: &str, weights: &str) -> anyhow::Result<()> {
+    let evaluator = tuner::tune_file(positions, weights, TUNE_ITERATIONS)?;
+    println!("Tuned weights written to {}:\n{}", weights, evaluator);
+    Ok(())
+}
+
+fn learn(games: usize, weights: &str) -> anyhow::Result<()> {
+    let config = TdConfig {
+        games,
+        checkpoint_path: Some(weights.into()),
+        ..Default::default()
+    };
+    let start = Evaluator::load(weights).unwrap_or_default();
+    learning::train(&config, start, |progress| {
+        println!(
+            "{} games: +{} ={} -{} against baseline ({:.0}%), weights {:?}",
+            progress.games_played,
+            progress.wins,
+            progress.draws,
+            progress.losses,
+            progress.score() * 100.0,
+            progress.evaluator.weights()
+        );
+    })?;
+    println!("Learned weights written to {}", weights);
+    Ok(())
 }
@@ -1,26 +1,112 @@
-use std::collections::HashSet;
+use std::sync::Arc;
 
-use crate::{board::Board, bot::Bot, checkers::Checkers, player::Player};
+use crate::{
+    board::Board,
+    bot::{Assessment, Bot},
+    checkers::Checkers,
+    constants::WIN_SCORE,
+    evaluation::Evaluator,
+    nnue::Network,
+    outcome::GameOutcome,
+    player::Player,
+    ruleset::WinCondition,
+};
 
-#[derive(Clone, Debug)]
-pub struct MinMax;
+/// Steps searched ahead by [`MinMax::assess`] before deciding on resigning or a draw.
+const ASSESSMENT_DEPTH: usize = 4;
+
+/// How far below the value of a draw the bot's position has to be before it wants one.
+const DRAW_MARGIN: i64 = 50;
+
+/// Outcome of a [`MinMax::search`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SearchResult {
+    /// Position to pass to [`Checkers::make_a_move_from_api`], `None` if there is no move.
+    pub best_move: Option<usize>,
+    /// Score of the best move from the point of view of the player to move.
+    pub score: i64,
+    /// Number of positions visited.
+    pub nodes: u64,
+}
+
+/// Alpha-beta bot.
+///
+/// `contempt` is how much worse than an equal position the bot considers a draw, in the same
+/// units as its evaluation (one man is worth 100 with the default weights). A positive contempt
+/// makes the bot avoid repeating positions when it thinks it is the stronger side, a negative
+/// contempt makes it steer towards a draw.
+///
+/// With a [`Network`] the bot evaluates positions with it instead of the linear `evaluator`, on
+/// boards with as many positions as the network has inputs for. On other boards it falls back
+/// to the linear `evaluator`.
+#[derive(Clone, Default, Debug)]
+pub struct MinMax {
+    evaluator: Evaluator,
+    network: Option<Arc<Network>>,
+    contempt: i64,
+}
 
 impl Bot for MinMax {
-    fn get_next_move(&self, game: &Checkers, depth_limit: Option<usize>) -> usize {
-        self.next_move(game, depth_limit, false)
+    fn get_next_move(&self, game: &Checkers, depth_limit: Option<usize>) -> Option<usize> {
+        self.run_search(game, depth_limit, false).best_move
+    }
+
+    fn get_next_move_with_display(
+        &self,
+        game: &Checkers,
+        depth_limit: Option<usize>,
+    ) -> Option<usize> {
+        self.run_search(game, depth_limit, true).best_move
     }
 
-    fn get_next_move_with_display(&self, game: &Checkers, depth_limit: Option<usize>) -> usize {
-        self.next_move(game, depth_limit, true)
+    /// Resigns once a short search finds a forced loss, and wants a draw when the position is
+    /// clearly worse than one.
+    fn assess(&self, game: &Checkers) -> Assessment {
+        let score = self.search(game, Some(ASSESSMENT_DEPTH)).score;
+        if score <= -WIN_SCORE / 2 {
+            Assessment::Resign
+        } else if score < self.draw_score() - DRAW_MARGIN {
+            Assessment::WantsDraw
+        } else {
+            Assessment::PlayOn
+        }
     }
 }
 
 impl MinMax {
-    fn next_move(&self, game: &Checkers, depth_limit: Option<usize>, display: bool) -> usize {
-        let mut game = game.clone();
+    pub fn new(evaluator: Evaluator, contempt: i64) -> Self {
+        Self {
+            evaluator,
+            network: None,
+            contempt,
+        }
+    }
+
+    pub fn with_network(mut self, network: Arc<Network>) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// Searches the game to `depth_limit` steps, without printing anything.
+    pub fn search(&self, game: &Checkers, depth_limit: Option<usize>) -> SearchResult {
+        self.run_search(game, depth_limit, false)
+    }
+
+    fn run_search(
+        &self,
+        game: &Checkers,
+        depth_limit: Option<usize>,
+        display: bool,
+    ) -> SearchResult {
+        let mut game = game.analysis_copy();
+        if let Some(network) = self.network_for(game.board()) {
+            game.board_mut()
+                .attach_network(network.clone())
+                .expect("networks are only used on boards they fit");
+        }
         let bot_player = game.current_player();
-        let mut visited = HashSet::new();
-        self.evaluate_moves(
+        let mut nodes = 0;
+        let (score, best_move) = self.evaluate_moves(
             &mut game,
             bot_player,
             depth_limit,
@@ -28,12 +114,38 @@ impl MinMax {
             i64::MIN,
             i64::MAX,
             display,
-            &mut visited,
-        )
-        .1
-        .expect("should always make some move")
+            &mut nodes,
+        );
+        SearchResult {
+            best_move,
+            score,
+            nodes,
+        }
+    }
+
+    /// The network, if it fits `board`.
+    fn network_for(&self, board: &Board) -> Option<&Arc<Network>> {
+        self.network.as_ref().filter(|n| n.fits(board).is_ok())
+    }
+
+    /// Score of a drawn position from the point of view of the bot.
+    fn draw_score(&self) -> i64 {
+        -self.contempt
+    }
+
+    fn evaluate_position(&self, game: &Checkers, bot_player: Player) -> i64 {
+        match (self.network_for(game.board()), game.rules().win_condition) {
+            (_, WinCondition::Giveaway) => {
+                self.evaluator.evaluate_giveaway(game.board(), bot_player)
+            }
+            (Some(network), WinCondition::Standard) => network
+                .evaluate(game.board(), bot_player)
+                .expect("networks are only used on boards they fit"),
+            (None, WinCondition::Standard) => self.evaluator.evaluate(game.board(), bot_player),
+        }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn evaluate_moves(
         &self,
         game: &mut Checkers,
@@ -43,39 +155,52 @@ impl MinMax {
         mut alpha: i64,
         mut beta: i64,
         display_simulations: bool,
-        visited_set: &mut HashSet<Board>,
+        nodes: &mut u64,
     ) -> (i64, Option<usize>) {
-        // base case: can't move (self or other) OR depth reached
+        *nodes += 1;
+
+        // base case: can't move (self or other), position repeated OR depth reached
         //      do: calculate score, return score and move
         // recurse case: not (win or lose)
         //      do: recurse for each valid move, return best
 
+        // a position seen before (in the game or earlier in this line) is scored as a draw,
+        // otherwise both sides could shuffle kings back and forth forever
+        if depth > 0
+            && game.selected_piece().is_none()
+            && (game.position_repetitions() > 1
+                || matches!(game.outcome(), Some(GameOutcome::Draw(_))))
+        {
+            return (self.draw_score(), None);
+        }
+
         let is_current_player = game.current_player() == bot_player; // bot always maximizing
         let mut best_score = if is_current_player {
             i64::MIN
         } else {
             i64::MAX
         };
-        let board = game.board();
-        if visited_set.contains(board) {
-            return (best_score, None);
-        }
-        // visited_set.insert(board.clone());
 
         #[cfg(feature = "standalone")]
         if display_simulations {
             game.show_board();
         }
 
-        let valid_moves: Vec<usize> = dbg!(game.selectable_positions().to_vec());
+        let valid_moves: Vec<usize> = game.selectable_positions().to_vec();
         let mut best_move = None;
-        if valid_moves.is_empty() {
-            let score: i64 = game.get_player_piece_count(bot_player) as i64
-                - game.get_player_piece_count(bot_player.other()) as i64;
-            (score, best_move)
+        if let Some(winner) = game.winner() {
+            // prefer the quickest win and the slowest loss
+            let score = WIN_SCORE - depth as i64;
+            match winner == bot_player {
+                true => (score, best_move),
+                false => (-score, best_move),
+            }
+        } else if depth_limit.is_some_and(|limit| depth >= limit) {
+            (self.evaluate_position(game, bot_player), best_move)
         } else {
             for pos in valid_moves.into_iter() {
-                game.make_a_move_from_api(pos);
+                game.make_a_move_from_api(pos)
+                    .expect("selectable positions can be played");
                 let new_score = self
                     .evaluate_moves(
                         game,
@@ -85,7 +210,7 @@ impl MinMax {
                         alpha,
                         beta,
                         display_simulations,
-                        visited_set,
+                        nodes,
                     )
                     .0;
                 if match is_current_player {
@@ -95,7 +220,7 @@ impl MinMax {
                     best_score = new_score;
                     best_move = Some(pos);
                 }
-                game.undo_last_move();
+                game.undo_last_move().expect("a step was just played");
                 match is_current_player {
                     true => alpha = alpha.max(best_score),
                     false => beta = beta.min(best_score),
@@ -108,3 +233,128 @@ impl MinMax {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        piece::Piece,
+        ruleset::{Movement, Ruleset},
+    };
+
+    /// Red and white kings shuffle away and back, leaving red to move in a position where
+    /// moving the red king to 9 repeats the position after red's first move.
+    fn shuffled_kings() -> Checkers {
+        let mut layout = vec![None; 32];
+        let mut red = Piece::red();
+        red.to_king();
+        let mut white = Piece::white();
+        white.to_king();
+        layout[13] = Some(red);
+        layout[30] = Some(white);
+        let mut game = Checkers::setup(
+            Board::with_layout(8, Movement::Diagonal, layout).unwrap(),
+            Player::Red,
+            None,
+        )
+        .unwrap();
+        for pos in [13, 9, 30, 25, 9, 13, 25, 30] {
+            game.make_a_move_from_api(pos).unwrap();
+        }
+        game
+    }
+
+    fn play_bot_turn(game: &mut Checkers, bot: &MinMax) {
+        let player = game.current_player();
+        while game.current_player() == player {
+            let pos = bot.get_next_move(game, Some(4)).unwrap();
+            game.make_a_move_from_api(pos).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_draw_seeking_bot_repeats() {
+        let mut game = shuffled_kings();
+        play_bot_turn(&mut game, &MinMax::new(Evaluator::default(), -1000));
+        assert!(game.board().get(9).is_some());
+        assert_eq!(game.position_repetitions(), 2);
+    }
+
+    #[test]
+    fn test_contemptuous_bot_avoids_repetition() {
+        let mut game = shuffled_kings();
+        play_bot_turn(&mut game, &MinMax::new(Evaluator::default(), 1000));
+        assert!(game.board().get(9).is_none());
+        assert_eq!(game.position_repetitions(), 1);
+    }
+
+    #[test]
+    fn test_giveaway_bot_gives_pieces_away() {
+        // moving to 14 lets white capture red's last piece, winning for red
+        let mut layout = vec![None; 32];
+        layout[9] = Some(Piece::red());
+        layout[18] = Some(Piece::white());
+        let mut game = Checkers::with_rules(Ruleset::giveaway(), None).unwrap();
+        *game.board_mut() = Board::with_layout(8, Movement::Diagonal, layout).unwrap();
+        game.start_headless();
+        let bot = MinMax::default();
+        play_bot_turn(&mut game, &bot);
+        assert!(game.board().get(14).is_some());
+        play_bot_turn(&mut game, &bot);
+        assert_eq!(game.winner(), Some(Player::Red));
+    }
+
+    #[test]
+    fn test_network_for_another_board_size_falls_back() {
+        let network = Network::new(32, 1, vec![1; 32 * 4], vec![0], vec![1], 0).unwrap();
+        let bot = MinMax::default().with_network(Arc::new(network));
+        let game = Checkers::with_rules(Ruleset::international(), None).unwrap();
+        assert_eq!(
+            bot.search(&game, Some(2)),
+            MinMax::default().search(&game, Some(2))
+        );
+    }
+
+    #[test]
+    fn test_assessment() {
+        let mut game = Checkers::new(None);
+        game.start_headless();
+        assert_eq!(MinMax::default().assess(&game), Assessment::PlayOn);
+
+        // red's only move lets the king on 9 capture its last man
+        let mut layout = vec![None; 32];
+        let mut king = Piece::white();
+        king.to_king();
+        layout[0] = Some(Piece::red());
+        layout[4] = Some(king);
+        layout[9] = Some(king);
+        let game = Checkers::setup(
+            Board::with_layout(8, Movement::Diagonal, layout.clone()).unwrap(),
+            Player::Red,
+            None,
+        )
+        .unwrap();
+        assert_eq!(MinMax::default().assess(&game), Assessment::Resign);
+
+        // with the kings further away red is only losing
+        layout[4] = None;
+        layout[9] = None;
+        layout[30] = Some(king);
+        layout[31] = Some(king);
+        let game = Checkers::setup(
+            Board::with_layout(8, Movement::Diagonal, layout).unwrap(),
+            Player::Red,
+            None,
+        )
+        .unwrap();
+        assert_eq!(MinMax::default().assess(&game), Assessment::WantsDraw);
+    }
+
+    #[test]
+    fn test_search_ignores_take_back_limit() {
+        let game = Checkers::new(None).with_take_back_limit(Some(1));
+        let minmax = MinMax::default();
+        assert!(minmax.get_next_move(&game, Some(4)).is_some());
+        assert_eq!(minmax.assess(&game), Assessment::PlayOn);
+    }
+}
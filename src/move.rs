@@ -30,6 +30,17 @@ impl Move {
         }
     }
 
+    /// The removal of the opponent's piece on `position` for not capturing when it could.
+    pub fn new_huff(position: usize, piece: Piece) -> Self {
+        Self {
+            start: position,
+            end: position,
+            started_king: piece.is_king(),
+            ended_king: piece.is_king(),
+            capture: Some((position, piece)),
+        }
+    }
+
     pub fn new_capture(
         start: usize,
         end: usize,
@@ -62,9 +73,19 @@ impl Move {
         self.capture
     }
 
+    #[inline]
+    pub fn is_select(&self) -> bool {
+        self.start == self.end && self.capture.is_none()
+    }
+
+    #[inline]
+    pub fn is_huff(&self) -> bool {
+        self.start == self.end && self.capture.is_some()
+    }
+
     #[inline]
     pub fn is_capture(&self) -> bool {
-        self.capture.is_some()
+        self.capture.is_some() && !self.is_huff()
     }
 
     pub fn started_king(&self) -> bool {
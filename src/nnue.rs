@@ -0,0 +1,385 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::{anyhow, bail, Context};
+
+use crate::{
+    board::Board,
+    error::CheckersError,
+    learning::{play_bot_game, random_opening, XorShift},
+    minmax::MinMax,
+    piece::Piece,
+    player::Player,
+};
+
+/// Network file loaded by [`crate::bot_choice::BotChoice::MinMaxNnue`] bots at startup.
+pub const NETWORK_FILE: &str = "network.nnue";
+
+const MAGIC: &[u8; 4] = b"CKNN";
+const VERSION: u32 = 1;
+
+/// Largest value passed on by the hidden layer, hidden values are clamped to `0..=ACTIVATION_MAX`.
+const ACTIVATION_MAX: i32 = 255;
+
+/// Kinds of piece that get their own set of inputs: red man, red king, white man, white king.
+const PIECE_KINDS: usize = 4;
+
+/// A small neural network evaluator in the style of NNUE.
+///
+/// There is one input for each kind of piece on each position, one hidden layer and a single
+/// output, the score from red's point of view in the same units as the linear evaluator.
+/// Since a move only changes a handful of inputs, the hidden layer sums (the [`Accumulator`])
+/// are updated incrementally by [`Board`] rather than recomputed for every position searched.
+///
+/// Weight files are little endian: the magic bytes `CKNN`, a `u32` version, `u32` position
+/// count and `u32` hidden size, then as `i16`s the input weights (hidden size weights for each
+/// input in turn), hidden biases and output weights, and finally the output bias as an `i32`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Network {
+    position_count: usize,
+    hidden_size: usize,
+    input_weights: Vec<i16>,
+    hidden_biases: Vec<i16>,
+    output_weights: Vec<i16>,
+    output_bias: i32,
+}
+
+impl Network {
+    pub fn new(
+        position_count: usize,
+        hidden_size: usize,
+        input_weights: Vec<i16>,
+        hidden_biases: Vec<i16>,
+        output_weights: Vec<i16>,
+        output_bias: i32,
+    ) -> anyhow::Result<Self> {
+        if input_weights.len() != position_count * PIECE_KINDS * hidden_size {
+            bail!(
+                "expected {} input weights",
+                position_count * PIECE_KINDS * hidden_size
+            );
+        }
+        if hidden_biases.len() != hidden_size || output_weights.len() != hidden_size {
+            bail!("expected {} hidden biases and output weights", hidden_size);
+        }
+        Ok(Self {
+            position_count,
+            hidden_size,
+            input_weights,
+            hidden_biases,
+            output_weights,
+            output_bias,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .with_context(|| format!("failed to read network from {}", path.display()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.to_bytes())
+            .with_context(|| format!("failed to write network to {}", path.display()))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut reader = Reader(bytes);
+        if reader.take(4)? != MAGIC {
+            bail!("not a network file");
+        }
+        let version = reader.u32()?;
+        if version != VERSION {
+            bail!("unsupported network version {}", version);
+        }
+        let position_count = reader.u32()? as usize;
+        let hidden_size = reader.u32()? as usize;
+        let input_count = position_count
+            .checked_mul(PIECE_KINDS)
+            .and_then(|n| n.checked_mul(hidden_size))
+            .ok_or_else(|| anyhow!("network is too large"))?;
+        let input_weights = reader.i16s(input_count)?;
+        let hidden_biases = reader.i16s(hidden_size)?;
+        let output_weights = reader.i16s(hidden_size)?;
+        let output_bias = reader.u32()? as i32;
+        if !reader.0.is_empty() {
+            bail!("unexpected data after the network");
+        }
+        Self::new(
+            position_count,
+            hidden_size,
+            input_weights,
+            hidden_biases,
+            output_weights,
+            output_bias,
+        )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(VERSION.to_le_bytes());
+        bytes.extend((self.position_count as u32).to_le_bytes());
+        bytes.extend((self.hidden_size as u32).to_le_bytes());
+        for weights in [
+            &self.input_weights,
+            &self.hidden_biases,
+            &self.output_weights,
+        ] {
+            bytes.extend(weights.iter().flat_map(|w| w.to_le_bytes()));
+        }
+        bytes.extend(self.output_bias.to_le_bytes());
+        bytes
+    }
+
+    pub fn position_count(&self) -> usize {
+        self.position_count
+    }
+
+    /// Checks that the network was made for boards with as many positions as `board`.
+    pub fn fits(&self, board: &Board) -> Result<(), CheckersError> {
+        if self.position_count != board.position_count() {
+            return Err(CheckersError::NetworkMismatch {
+                network: self.position_count,
+                board: board.position_count(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Score of the board from the point of view of `player`, or an error if the network does
+    /// not fit the board. Uses the accumulator of the board when this network is attached to
+    /// it, otherwise computes the hidden layer from scratch.
+    pub fn evaluate(&self, board: &Board, player: Player) -> Result<i64, CheckersError> {
+        self.fits(board)?;
+        let score = match board.accumulator() {
+            Some(accumulator) if std::ptr::eq(accumulator.network.as_ref(), self) => {
+                self.output(&accumulator.values)
+            }
+            _ => self.output(&self.hidden_values(board)),
+        };
+        Ok(match player {
+            Player::Red => score,
+            Player::White => -score,
+        })
+    }
+
+    fn hidden_values(&self, board: &Board) -> Vec<i32> {
+        let mut values: Vec<i32> = self.hidden_biases.iter().map(|b| *b as i32).collect();
+        for pos in 0..board.position_count() {
+            if let Some(piece) = board.get(pos) {
+                for (v, w) in values.iter_mut().zip(self.weights_for(pos, piece)) {
+                    *v += *w as i32;
+                }
+            }
+        }
+        values
+    }
+
+    fn weights_for(&self, position: usize, piece: Piece) -> &[i16] {
+        let kind = match (piece.player(), piece.is_king()) {
+            (Player::Red, false) => 0,
+            (Player::Red, true) => 1,
+            (Player::White, false) => 2,
+            (Player::White, true) => 3,
+        };
+        let input = kind * self.position_count + position;
+        &self.input_weights[input * self.hidden_size..(input + 1) * self.hidden_size]
+    }
+
+    fn output(&self, hidden_values: &[i32]) -> i64 {
+        let sum: i64 = hidden_values
+            .iter()
+            .zip(&self.output_weights)
+            .map(|(v, w)| (*v).clamp(0, ACTIVATION_MAX) as i64 * *w as i64)
+            .sum();
+        (sum + self.output_bias as i64) / ACTIVATION_MAX as i64
+    }
+}
+
+/// The hidden layer sums of a [`Network`] for the pieces on a [`Board`].
+#[derive(Clone, Debug)]
+pub struct Accumulator {
+    network: Arc<Network>,
+    values: Vec<i32>,
+}
+
+impl Accumulator {
+    pub(crate) fn new(network: Arc<Network>, board: &Board) -> Result<Self, CheckersError> {
+        network.fits(board)?;
+        let values = network.hidden_values(board);
+        Ok(Self { network, values })
+    }
+
+    #[inline]
+    pub(crate) fn add(&mut self, position: usize, piece: Piece) {
+        for (v, w) in self
+            .values
+            .iter_mut()
+            .zip(self.network.weights_for(position, piece))
+        {
+            *v += *w as i32;
+        }
+    }
+
+    #[inline]
+    pub(crate) fn remove(&mut self, position: usize, piece: Piece) {
+        for (v, w) in self
+            .values
+            .iter_mut()
+            .zip(self.network.weights_for(position, piece))
+        {
+            *v -= *w as i32;
+        }
+    }
+}
+
+/// Plays `games` games between two [`MinMax`] bots searching `depth` steps, each from a few
+/// random opening turns, and writes every position reached with the result of its game.
+///
+/// The output has the format read by [`crate::tuner::load_positions`], one position per line,
+/// so it can be used both for training a network and for tuning the linear evaluator. Returns
+/// the number of positions written.
+pub fn export_training_data(
+    path: impl AsRef<Path>,
+    games: usize,
+    depth: usize,
+    seed: u64,
+) -> anyhow::Result<usize> {
+    const OPENING_TURNS: usize = 4;
+    const MAX_TURNS: usize = 150;
+
+    let path = path.as_ref();
+    let file = File::create(path)
+        .with_context(|| format!("failed to create training data {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    let mut rng = XorShift(seed.max(1));
+    let bot = MinMax::default();
+    let mut written = 0;
+    for _ in 0..games {
+        let mut boards = Vec::new();
+        let opening = random_opening(OPENING_TURNS, &mut rng);
+        let outcome = play_bot_game(opening, &bot, &bot, depth, MAX_TURNS, None, |game| {
+            boards.push(game.board().encode())
+        });
+        let result = match outcome.winner() {
+            Some(Player::Red) => "1",
+            Some(Player::White) => "0",
+            None => "0.5",
+        };
+        for board in boards {
+            writeln!(writer, "{} {}", board, result)?;
+            written += 1;
+        }
+    }
+    writer.flush()?;
+    Ok(written)
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> anyhow::Result<&'a [u8]> {
+        if self.0.len() < n {
+            return Err(anyhow!("network file is truncated"));
+        }
+        let (taken, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> anyhow::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn i16s(&mut self, n: usize) -> anyhow::Result<Vec<i16>> {
+        let bytes = n
+            .checked_mul(2)
+            .ok_or_else(|| anyhow!("network is too large"))?;
+        Ok(self
+            .take(bytes)?
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ruleset::Movement;
+
+    fn test_network() -> Network {
+        let hidden_size = 4;
+        let input_weights = (0..32 * PIECE_KINDS * hidden_size)
+            .map(|i| (i * 37 % 101) as i16 - 50)
+            .collect();
+        Network::new(
+            32,
+            hidden_size,
+            input_weights,
+            vec![10, 20, 30, 40],
+            vec![100, -200, 300, -50],
+            7,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_network_for_another_board_size_is_refused() {
+        let network = Arc::new(test_network());
+        for size in [6, 10] {
+            let mut board = Board::new(size).unwrap();
+            let mismatch = CheckersError::NetworkMismatch {
+                network: 32,
+                board: board.position_count(),
+            };
+            assert_eq!(network.evaluate(&board, Player::Red), Err(mismatch.clone()));
+            assert_eq!(board.attach_network(network.clone()), Err(mismatch));
+            assert!(board.accumulator().is_none());
+        }
+    }
+
+    #[test]
+    fn test_incremental_matches_full_evaluation() {
+        let network = Arc::new(test_network());
+        let mut board = Board::new(8).unwrap();
+        board.attach_network(network.clone()).unwrap();
+        board.r#move(8, 12);
+        board.take(21);
+        board.crown(12);
+        board.set(21, Some(Piece::red()));
+
+        let mut fresh = board.clone();
+        fresh.attach_network(network.clone()).unwrap();
+        assert_eq!(
+            board.accumulator().unwrap().values,
+            fresh.accumulator().unwrap().values
+        );
+        let detached = Board::with_layout(
+            8,
+            Movement::Diagonal,
+            (0..32).map(|i| board.get(i)).collect(),
+        )
+        .unwrap();
+        assert_eq!(
+            network.evaluate(&board, Player::White),
+            network.evaluate(&detached, Player::White)
+        );
+    }
+
+    #[test]
+    fn test_weight_file_round_trip() {
+        let network = test_network();
+        assert_eq!(Network::from_bytes(&network.to_bytes()).unwrap(), network);
+        assert!(Network::from_bytes(&network.to_bytes()[..20]).is_err());
+        let mut huge = network.to_bytes();
+        huge[8..16].copy_from_slice(&[0xff; 8]);
+        assert!(Network::from_bytes(&huge).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::Display;
+
+use crate::{board::Board, evaluation::Evaluator, player::Player};
+
+/// How a finished game ended.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameOutcome {
+    Win { winner: Player, reason: WinReason },
+    Draw(DrawReason),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WinReason {
+    /// The loser had no pieces left or could not move.
+    NoMoves,
+    /// The winner had no pieces left or could not move, under
+    /// [`crate::ruleset::WinCondition::Giveaway`] rules.
+    GaveAway,
+    Resignation,
+    /// The game was stopped and given to the player who was clearly ahead.
+    Adjudication,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DrawReason {
+    /// The same position occurred three times with the same player to move.
+    Repetition,
+    /// The rules' limit of turns without a capture or a man moving was reached.
+    MoveLimit,
+    /// A player claimed a draw after enough turns without a capture or a man moving.
+    InsufficientProgress,
+    /// Both players agreed to a draw.
+    Agreement,
+    /// The game was stopped with neither player clearly ahead.
+    Adjudication,
+}
+
+/// Something done in a game besides moving, kept in the history of the game.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameAction {
+    Resign(Player),
+    OfferDraw(Player),
+    AcceptDraw(Player),
+    DeclineDraw(Player),
+    /// The game was stopped with this outcome by the program running it.
+    Adjudicate(GameOutcome),
+    /// The player took back their last turn together with the opponent's reply. Recorded with
+    /// the turn play went back to, and kept when turns are undone.
+    TakeBack(Player),
+}
+
+/// Rule for ending bot games early, see [`crate::learning::play_bot_game`]: once `evaluator`
+/// has scored the position at least `threshold` in favour of the same player at the start of
+/// `turns` turns in a row, that player wins.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Adjudication {
+    pub threshold: i64,
+    pub turns: usize,
+    pub evaluator: Evaluator,
+}
+
+impl Adjudication {
+    /// The player at least `threshold` ahead on `board`, if either is.
+    pub fn leader(&self, board: &Board) -> Option<Player> {
+        let score = self.evaluator.evaluate(board, Player::Red);
+        if score >= self.threshold {
+            Some(Player::Red)
+        } else if score <= -self.threshold {
+            Some(Player::White)
+        } else {
+            None
+        }
+    }
+}
+
+impl GameOutcome {
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            GameOutcome::Win { winner, .. } => Some(*winner),
+            GameOutcome::Draw(_) => None,
+        }
+    }
+}
+
+impl Display for GameOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameOutcome::Win { winner, reason } => {
+                write!(f, "{} wins", winner)?;
+                match reason {
+                    WinReason::NoMoves => write!(f, ", the other player cannot move"),
+                    WinReason::GaveAway => write!(f, " by running out of moves"),
+                    WinReason::Resignation => write!(f, " by resignation"),
+                    WinReason::Adjudication => write!(f, " by adjudication"),
+                }
+            }
+            GameOutcome::Draw(reason) => write!(
+                f,
+                "Draw by {}",
+                match reason {
+                    DrawReason::Repetition => "threefold repetition",
+                    DrawReason::MoveLimit => "the move limit",
+                    DrawReason::InsufficientProgress => "claim of insufficient progress",
+                    DrawReason::Agreement => "agreement",
+                    DrawReason::Adjudication => "adjudication",
+                }
+            ),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{checkers::Checkers, turn::Turn};
+
+/// Counts the ways the game can continue for `depth` complete turns, where a turn is
+/// everything a player does before play passes to the other side, so a multi-jump counts
+/// as a single turn.
+pub fn perft(game: &Checkers, depth: usize) -> u64 {
+    let mut game = game.analysis_copy();
+    count_turns(&mut game, depth)
+}
+
+/// [`perft`] broken down by the first turn, with the number of continuations after each.
+pub fn divide(game: &Checkers, depth: usize) -> Vec<(Turn, u64)> {
+    let mut game = game.analysis_copy();
+    game.legal_turns()
+        .into_iter()
+        .map(|turn| {
+            game.apply_turn(&turn).expect("legal turns can be applied");
+            let count = count_turns(&mut game, depth.saturating_sub(1));
+            game.undo().expect("a turn was just played");
+            (turn, count)
+        })
+        .collect()
+}
+
+fn count_turns(game: &mut Checkers, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let player = game.current_player();
+    let mut count = 0;
+    for pos in game.selectable_positions().to_vec() {
+        game.make_a_move_from_api(pos)
+            .expect("selectable positions can be played");
+        count += if game.current_player() == player {
+            // still the same turn, keep going until it is finished
+            count_turns(game, depth)
+        } else {
+            count_turns(game, depth - 1)
+        };
+        game.undo_last_move().expect("a step was just played");
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::Board,
+        piece::Piece,
+        player::Player,
+        ruleset::{Movement, Ruleset},
+    };
+
+    /// Published perft results for the start position of English checkers.
+    const START_PERFT: [u64; 9] = [1, 7, 49, 302, 1469, 7361, 36768, 179740, 845931];
+
+    fn start_position() -> Checkers {
+        Checkers::setup(Board::new(8).unwrap(), Player::Red, None).unwrap()
+    }
+
+    #[test]
+    fn test_perft_start_position() {
+        let game = start_position();
+        for (depth, expected) in START_PERFT.iter().enumerate().take(7) {
+            assert_eq!(perft(&game, depth), *expected, "depth {}", depth);
+        }
+    }
+
+    #[test]
+    #[ignore = "slow in debug builds, run with --release --ignored"]
+    fn test_perft_start_position_deep() {
+        let game = start_position();
+        for (depth, expected) in START_PERFT.iter().enumerate().skip(7) {
+            assert_eq!(perft(&game, depth), *expected, "depth {}", depth);
+        }
+    }
+
+    #[test]
+    fn test_perft_international() {
+        // published results for the start position of international draughts
+        let mut game = Checkers::with_rules(Ruleset::international(), None).unwrap();
+        game.start_headless();
+        for (depth, expected) in [1, 9, 81, 658, 4265].iter().enumerate() {
+            assert_eq!(perft(&game, depth), *expected, "depth {}", depth);
+        }
+    }
+
+    #[test]
+    fn test_perft_russian() {
+        // published results for the start position of russian draughts
+        let mut game = Checkers::with_rules(Ruleset::russian(), None).unwrap();
+        game.start_headless();
+        for (depth, expected) in [1, 7, 49, 302, 1469, 7482, 37986].iter().enumerate() {
+            assert_eq!(perft(&game, depth), *expected, "depth {}", depth);
+        }
+    }
+
+    #[test]
+    fn test_divide_sums_to_perft() {
+        let game = start_position();
+        let divided = divide(&game, 4);
+        assert_eq!(divided.len(), 7);
+        assert_eq!(divided.iter().map(|(_, n)| n).sum::<u64>(), perft(&game, 4));
+    }
+
+    #[test]
+    fn test_perft_ignores_take_back_limit() {
+        let game = start_position().with_take_back_limit(Some(1));
+        assert_eq!(perft(&game, 3), START_PERFT[3]);
+        assert_eq!(divide(&game, 2).len(), 7);
+    }
+
+    #[test]
+    fn test_perft_multi_jump() {
+        // a red man on 1 can jump 5 to land on 8, or jump 6 and then 14 to land on 17
+        let mut layout = vec![None; 32];
+        layout[1] = Some(Piece::red());
+        layout[5] = Some(Piece::white());
+        layout[6] = Some(Piece::white());
+        layout[14] = Some(Piece::white());
+        let game = Checkers::setup(
+            Board::with_layout(8, Movement::Diagonal, layout).unwrap(),
+            Player::Red,
+            None,
+        )
+        .unwrap();
+        let divided = divide(&game, 1);
+        assert_eq!(perft(&game, 1), 2);
+        assert!(divided.contains(&(Turn::new(vec![1, 8]), 1)));
+        assert!(divided.contains(&(Turn::new(vec![1, 10, 17]), 1)));
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crate::{constants::DIRECTIONS, player::Player};
+use crate::{player::Player, ruleset::Movement};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct Piece {
@@ -31,6 +31,7 @@ impl Piece {
         self.player
     }
 
+    #[allow(clippy::wrong_self_convention)]
     pub fn to_king(&mut self) {
         self.is_king = true;
     }
@@ -39,11 +40,18 @@ impl Piece {
         self.is_king = false;
     }
 
-    pub fn directions(&self) -> &[(usize, usize)] {
+    /// The directions this piece can move in with `movement`, forwards (and sideways when
+    /// orthogonal) for men and every way for kings.
+    pub fn directions(&self, movement: Movement) -> &'static [(usize, usize)] {
+        let all = movement.directions();
+        let forward = match movement {
+            Movement::Diagonal => 2,
+            Movement::Orthogonal => 3,
+        };
         match (self.is_king, self.player) {
-            (true, _) => &DIRECTIONS[..],
-            (false, Player::Red) => &DIRECTIONS[..2],
-            (false, Player::White) => &DIRECTIONS[2..],
+            (true, _) => all,
+            (false, Player::Red) => &all[..forward],
+            (false, Player::White) => &all[all.len() - forward..],
         }
     }
 }
@@ -0,0 +1,301 @@
+use crate::{
+    constants::{DIRECTIONS, ORTHOGONAL_DIRECTIONS},
+    player::Player,
+};
+
+/// The lines pieces move along, which also decides which squares of the board are played on.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Movement {
+    /// Along diagonals, using only the dark squares.
+    #[default]
+    Diagonal,
+    /// Along rows and columns, using every square. Men move forwards and sideways.
+    Orthogonal,
+}
+
+impl Movement {
+    /// Every direction a king can move in.
+    pub fn directions(self) -> &'static [(usize, usize)] {
+        match self {
+            Movement::Diagonal => &DIRECTIONS,
+            Movement::Orthogonal => &ORTHOGONAL_DIRECTIONS,
+        }
+    }
+}
+
+/// Who wins when a player has no pieces left or cannot move on their turn.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum WinCondition {
+    /// The player who cannot move loses.
+    #[default]
+    Standard,
+    /// The player who cannot move wins, so each side tries to give all of its pieces away.
+    Giveaway,
+}
+
+/// How far a king can travel along a line in one step.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KingRange {
+    /// One square for a move, or jumping an adjacent piece to the square just beyond it.
+    Short,
+    /// Any number of empty squares, and a capture may start and land any distance away from
+    /// the captured piece as long as every square passed over is empty.
+    Flying,
+}
+
+/// Which capture sequences may be chosen when several are available.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CapturePrecedence {
+    /// Any capture sequence.
+    Free,
+    /// Only the sequences that capture the most pieces.
+    MostPieces,
+    /// The italian chain: the most pieces, then capturing with a king rather than a man, then
+    /// the most kings captured, then the sequence that captures a king earliest.
+    Italian,
+}
+
+/// When the pieces captured in a sequence leave the board.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CaptureRemoval {
+    /// At the end of the turn. Until then they cannot be jumped again, and the capturing piece
+    /// can neither land on nor pass over their squares.
+    EndOfTurn,
+    /// As soon as they are jumped, so their squares are empty for the rest of the sequence.
+    /// The capturing piece may not turn back along the line of its last capture.
+    Immediate,
+}
+
+/// When a man that reaches the far row becomes a king.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Promotion {
+    /// Crowned on arrival, which ends the turn even if further captures were possible.
+    EndsTurn,
+    /// Crowned on arrival and, in the middle of a capture, carries on capturing as a king.
+    ContinuesAsKing,
+    /// Crowned only if the turn ends on the far row. A man that passes over the far row in the
+    /// middle of a capture stays a man.
+    AtEndOfTurn,
+}
+
+/// The rules a [`crate::checkers::Checkers`] game is played by.
+///
+/// Every field is public so variants can be described as data:
+///
+/// ```
+/// use kar_checkers_minmax::ruleset::{KingRange, Ruleset};
+/// let rules = Ruleset {
+///     king_range: KingRange::Flying,
+///     ..Ruleset::english()
+/// };
+/// assert!(rules.mandatory_capture);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Ruleset {
+    /// Squares along each side of the board.
+    pub board_size: usize,
+    /// Number of rows for rectangular boards, `None` for square boards of `board_size` rows.
+    pub board_rows: Option<usize>,
+    /// Rows of men each player starts with.
+    pub starting_rows: usize,
+    /// Rows left empty behind each player's men at the start, e.g. 1 for turkish draughts.
+    pub empty_back_rows: usize,
+    pub movement: Movement,
+    /// Whether men can capture backwards as well as forwards. Men always move forwards.
+    pub men_capture_backwards: bool,
+    /// Whether men can capture kings.
+    pub men_capture_kings: bool,
+    /// House rule where a man that captures a king is crowned, just as if it had reached the
+    /// far row. Off in every standard variant.
+    pub king_capture_crowns: bool,
+    pub king_range: KingRange,
+    /// Whether a player that can capture has to.
+    pub mandatory_capture: bool,
+    /// Whether a player may remove ("huff") a piece of the opponent that could have captured
+    /// on the opponent's last turn but did not, before making their own turn. Only has an
+    /// effect when captures are not mandatory.
+    pub huffing: bool,
+    pub capture_precedence: CapturePrecedence,
+    pub capture_removal: CaptureRemoval,
+    pub promotion: Promotion,
+    pub win_condition: WinCondition,
+    /// Turns in a row without a capture or a man moving after which the game is drawn.
+    pub quiet_turn_limit: Option<usize>,
+    /// Turns in a row without a capture or a man moving after which the player to move may
+    /// claim a draw.
+    pub quiet_turn_claim: Option<usize>,
+    pub first_player: Player,
+}
+
+impl Ruleset {
+    /// English draughts, or American checkers: 8x8, men capture forwards only, short kings,
+    /// captures are mandatory but any sequence may be chosen, and promotion ends the turn. A
+    /// draw can be claimed after 40 moves each without a capture or a man moving.
+    pub fn english() -> Self {
+        Self {
+            board_size: 8,
+            board_rows: None,
+            starting_rows: 3,
+            empty_back_rows: 0,
+            movement: Movement::Diagonal,
+            men_capture_backwards: false,
+            men_capture_kings: true,
+            king_capture_crowns: false,
+            king_range: KingRange::Short,
+            mandatory_capture: true,
+            huffing: false,
+            capture_precedence: CapturePrecedence::Free,
+            capture_removal: CaptureRemoval::EndOfTurn,
+            promotion: Promotion::EndsTurn,
+            win_condition: WinCondition::Standard,
+            quiet_turn_limit: None,
+            quiet_turn_claim: Some(80),
+            first_player: Player::Red,
+        }
+    }
+
+    /// International draughts: 10x10 with 20 men each, men capture backwards, flying kings,
+    /// the sequence capturing the most pieces must be played, a man is only crowned if its turn
+    /// ends on the far row, and white moves first. The game is drawn after 25 moves each
+    /// without a capture or a man moving.
+    pub fn international() -> Self {
+        Self {
+            board_size: 10,
+            board_rows: None,
+            starting_rows: 4,
+            empty_back_rows: 0,
+            movement: Movement::Diagonal,
+            men_capture_backwards: true,
+            men_capture_kings: true,
+            king_capture_crowns: false,
+            king_range: KingRange::Flying,
+            mandatory_capture: true,
+            huffing: false,
+            capture_precedence: CapturePrecedence::MostPieces,
+            capture_removal: CaptureRemoval::EndOfTurn,
+            promotion: Promotion::AtEndOfTurn,
+            win_condition: WinCondition::Standard,
+            quiet_turn_limit: Some(50),
+            quiet_turn_claim: None,
+            first_player: Player::White,
+        }
+    }
+
+    /// Russian draughts: 8x8, men capture backwards, flying kings, any capture sequence may be
+    /// chosen, a man reaching the far row mid-capture is crowned and carries on capturing as a
+    /// king, and white moves first. The game is drawn after 15 moves each without a capture or
+    /// a man moving.
+    pub fn russian() -> Self {
+        Self {
+            board_size: 8,
+            board_rows: None,
+            starting_rows: 3,
+            empty_back_rows: 0,
+            movement: Movement::Diagonal,
+            men_capture_backwards: true,
+            men_capture_kings: true,
+            king_capture_crowns: false,
+            king_range: KingRange::Flying,
+            mandatory_capture: true,
+            huffing: false,
+            capture_precedence: CapturePrecedence::Free,
+            capture_removal: CaptureRemoval::EndOfTurn,
+            promotion: Promotion::ContinuesAsKing,
+            win_condition: WinCondition::Standard,
+            quiet_turn_limit: Some(30),
+            quiet_turn_claim: None,
+            first_player: Player::White,
+        }
+    }
+
+    /// Italian draughts: 8x8, men capture forwards only and cannot capture kings, short kings,
+    /// captures follow the [`CapturePrecedence::Italian`] chain, promotion ends the turn and
+    /// white moves first.
+    pub fn italian() -> Self {
+        Self {
+            board_size: 8,
+            board_rows: None,
+            starting_rows: 3,
+            empty_back_rows: 0,
+            movement: Movement::Diagonal,
+            men_capture_backwards: false,
+            men_capture_kings: false,
+            king_capture_crowns: false,
+            king_range: KingRange::Short,
+            mandatory_capture: true,
+            huffing: false,
+            capture_precedence: CapturePrecedence::Italian,
+            capture_removal: CaptureRemoval::EndOfTurn,
+            promotion: Promotion::EndsTurn,
+            win_condition: WinCondition::Standard,
+            quiet_turn_limit: None,
+            quiet_turn_claim: Some(80),
+            first_player: Player::White,
+        }
+    }
+
+    /// Turkish draughts: every square of an 8x8 board, orthogonal moves, 16 men each on the
+    /// second and third rows, men capture forwards and sideways, flying kings, the most pieces
+    /// must be captured, captured pieces are removed as they are jumped, promotion ends the
+    /// turn and white moves first.
+    pub fn turkish() -> Self {
+        Self {
+            board_size: 8,
+            board_rows: None,
+            starting_rows: 2,
+            empty_back_rows: 1,
+            movement: Movement::Orthogonal,
+            men_capture_backwards: false,
+            men_capture_kings: true,
+            king_capture_crowns: false,
+            king_range: KingRange::Flying,
+            mandatory_capture: true,
+            huffing: false,
+            capture_precedence: CapturePrecedence::MostPieces,
+            capture_removal: CaptureRemoval::Immediate,
+            promotion: Promotion::EndsTurn,
+            win_condition: WinCondition::Standard,
+            quiet_turn_limit: None,
+            quiet_turn_claim: Some(80),
+            first_player: Player::White,
+        }
+    }
+
+    /// Giveaway checkers: english rules, but the player who cannot move wins.
+    pub fn giveaway() -> Self {
+        Self {
+            win_condition: WinCondition::Giveaway,
+            ..Self::english()
+        }
+    }
+
+    /// English rules as played in casual club games, where captures are optional but a piece
+    /// that skips one can be huffed.
+    pub fn casual() -> Self {
+        Self {
+            mandatory_capture: false,
+            huffing: true,
+            ..Self::english()
+        }
+    }
+
+    /// The rules for a variant by name, as accepted on the command line.
+    pub fn named(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "english" | "american" | "checkers" => Some(Self::english()),
+            "international" => Some(Self::international()),
+            "russian" => Some(Self::russian()),
+            "italian" => Some(Self::italian()),
+            "turkish" => Some(Self::turkish()),
+            "giveaway" | "suicide" | "losing" => Some(Self::giveaway()),
+            "casual" | "huffing" => Some(Self::casual()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self::english()
+    }
+}
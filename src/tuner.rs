@@ -0,0 +1,151 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context};
+
+use crate::{
+    board::Board,
+    evaluation::{win_probability, Evaluator, FEATURE_COUNT},
+};
+
+/// Largest weight change tried by [`tune`], halved whenever no change improves the error.
+const INITIAL_STEP: i64 = 16;
+
+/// A position from a finished game together with the result of that game.
+#[derive(Clone, Debug)]
+pub struct LabelledPosition {
+    features: [i64; FEATURE_COUNT],
+    /// `1.0` if red won, `0.5` for a draw and `0.0` if white won.
+    result: f64,
+}
+
+impl LabelledPosition {
+    pub fn new(board: &Board, result: f64) -> Self {
+        Self {
+            features: Evaluator::features(board),
+            result,
+        }
+    }
+}
+
+/// Reads positions with one position per line: the board as written by [`Board::encode`], a
+/// space and the result from red's point of view (`1`, `0.5` or `0`). Empty lines and lines
+/// starting with `#` are skipped.
+pub fn load_positions(path: impl AsRef<Path>) -> anyhow::Result<Vec<LabelledPosition>> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read positions from {}", path.display()))?;
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'))
+        .map(|(line_number, line)| {
+            let (board, result) = line
+                .split_once(' ')
+                .ok_or_else(|| anyhow!("line {}: expected \"board result\"", line_number))?;
+            let board = Board::decode(board)
+                .with_context(|| format!("line {}: invalid board {:?}", line_number, board))?;
+            let result: f64 = result
+                .trim()
+                .parse()
+                .with_context(|| format!("line {}: invalid result", line_number))?;
+            if !(0.0..=1.0).contains(&result) {
+                return Err(anyhow!("line {}: result must be 0 to 1", line_number));
+            }
+            Ok(LabelledPosition::new(&board, result))
+        })
+        .collect()
+}
+
+/// Mean squared difference between the game results and the win probability predicted from
+/// the static score of each position.
+pub fn prediction_error(evaluator: &Evaluator, positions: &[LabelledPosition]) -> f64 {
+    error_with_weights(evaluator.weights(), positions)
+}
+
+fn error_with_weights(weights: &[i64; FEATURE_COUNT], positions: &[LabelledPosition]) -> f64 {
+    if positions.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = positions
+        .iter()
+        .map(|p| {
+            let score = Evaluator::score(weights, &p.features) as f64;
+            let predicted = win_probability(score);
+            (p.result - predicted).powi(2)
+        })
+        .sum();
+    total / positions.len() as f64
+}
+
+/// Texel tuning: repeatedly nudges each weight up or down, keeping any change that lowers the
+/// [`prediction_error`], until no change helps or `max_iterations` passes have been made.
+pub fn tune(start: Evaluator, positions: &[LabelledPosition], max_iterations: usize) -> Evaluator {
+    let mut weights = *start.weights();
+    let mut best_error = error_with_weights(&weights, positions);
+    let mut step = INITIAL_STEP;
+    for _ in 0..max_iterations {
+        let mut improved = false;
+        for i in 0..FEATURE_COUNT {
+            for delta in [step, -step] {
+                let mut candidate = weights;
+                candidate[i] += delta;
+                let error = error_with_weights(&candidate, positions);
+                if error < best_error {
+                    best_error = error;
+                    weights = candidate;
+                    improved = true;
+                    break;
+                }
+            }
+        }
+        if !improved {
+            if step == 1 {
+                break;
+            }
+            step /= 2;
+        }
+    }
+    Evaluator::new(weights)
+}
+
+/// Tunes the default weights against the positions in `positions_path` and writes the result
+/// to `weights_path`, where [`Evaluator::load`] can pick it up.
+pub fn tune_file(
+    positions_path: impl AsRef<Path>,
+    weights_path: impl AsRef<Path>,
+    max_iterations: usize,
+) -> anyhow::Result<Evaluator> {
+    let positions = load_positions(positions_path)?;
+    let evaluator = tune(Evaluator::default(), &positions, max_iterations);
+    evaluator.save(weights_path)?;
+    Ok(evaluator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{piece::Piece, ruleset::Movement};
+
+    fn board_with_extra_king(extra: Piece) -> Board {
+        let mut layout = vec![None; 32];
+        layout[0] = Some(Piece::red());
+        layout[31] = Some(Piece::white());
+        let mut king = extra;
+        king.to_king();
+        layout[14] = Some(king);
+        Board::with_layout(8, Movement::Diagonal, layout).unwrap()
+    }
+
+    #[test]
+    fn test_tune_learns_king_value() {
+        let positions = [
+            LabelledPosition::new(&board_with_extra_king(Piece::red()), 1.0),
+            LabelledPosition::new(&board_with_extra_king(Piece::white()), 0.0),
+        ];
+        let start = Evaluator::new([100, 0, 0, 0, 0]);
+        let tuned = tune(start, &positions, 1000);
+        assert!(tuned.weights()[1] > 0);
+        assert!(prediction_error(&tuned, &positions) < prediction_error(&start, &positions));
+    }
+}
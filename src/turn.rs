@@ -0,0 +1,30 @@
+/// Everything a player does in one turn, as the positions passed to
+/// [`crate::checkers::Checkers::make_a_move_from_api`] one after another: the piece to move
+/// followed by each square it lands on. A turn that starts by huffing has the huffed piece first.
+///
+/// # Example
+///
+/// ```
+/// use kar_checkers_minmax::{checkers::Checkers, turn::Turn};
+/// let mut game = Checkers::new(None);
+/// game.apply_turn(&Turn::new(vec![9, 13])).unwrap();
+/// assert_eq!(game.legal_turns().len(), 7);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Turn(Vec<usize>);
+
+impl Turn {
+    pub fn new(steps: Vec<usize>) -> Self {
+        Self(steps)
+    }
+
+    pub fn steps(&self) -> &[usize] {
+        &self.0
+    }
+}
+
+impl From<Vec<usize>> for Turn {
+    fn from(steps: Vec<usize>) -> Self {
+        Self(steps)
+    }
+}